@@ -1,38 +1,50 @@
+use crate::converter::CurveOptions;
+use crate::easyeda::EasyedaApiConfig;
 use crate::error::{AppError, Result};
-use clap::Parser;
+use crate::property_map::PropertyMap;
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::Duration;
 
 #[derive(Parser, Debug)]
 #[command(name = "nlbn")]
 #[command(version = env!("CARGO_PKG_VERSION"))]
 #[command(about = "Fast EasyEDA/LCSC to KiCad converter with parallel downloads", long_about = None)]
 pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     /// LCSC component ID (e.g., C2040)
-    #[arg(long, value_name = "ID", conflicts_with = "batch")]
+    #[arg(long, value_name = "ID", conflicts_with_all = ["batch", "from_file"])]
     pub lcsc_id: Option<String>,
 
     /// Batch mode: read LCSC IDs from a file (one ID per line)
-    #[arg(long, value_name = "FILE", conflicts_with = "lcsc_id")]
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["lcsc_id", "from_file"])]
     pub batch: Option<PathBuf>,
 
+    /// Convert an EasyEDA Std symbol/footprint source file exported from the editor
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["lcsc_id", "batch"])]
+    pub from_file: Option<PathBuf>,
+
     /// Convert symbol only
-    #[arg(long)]
+    #[arg(long, global = true)]
     pub symbol: bool,
 
     /// Convert footprint only
-    #[arg(long)]
+    #[arg(long, global = true)]
     pub footprint: bool,
 
     /// Convert 3D model only
-    #[arg(long = "3d")]
+    #[arg(long = "3d", global = true)]
     pub model_3d: bool,
 
     /// Convert all (symbol + footprint + 3D model)
-    #[arg(long)]
+    #[arg(long, global = true)]
     pub full: bool,
 
     /// Output directory path
-    #[arg(short, long, default_value = ".")]
+    #[arg(short, long, default_value = ".", global = true)]
     pub output: PathBuf,
 
     /// Base library name used under --output when explicit library targets are not provided
@@ -64,16 +76,148 @@ pub struct Cli {
     pub project_relative: bool,
 
     /// Enable debug logging
-    #[arg(long)]
+    #[arg(long, global = true)]
     pub debug: bool,
 
     /// Continue on error in batch mode (skip failed components)
     #[arg(long)]
     pub continue_on_error: bool,
 
-    /// Number of parallel downloads in batch mode (default: 4)
+    /// Number of parallel downloads in batch mode and for search lookups (default: 4)
     #[arg(long, default_value = "4")]
     pub parallel: usize,
+
+    /// Read saved API responses (<ID>.json) and 3D models (<uuid>.obj/.step) from this directory before querying EasyEDA
+    #[arg(long, value_name = "DIR")]
+    pub source_dir: Option<PathBuf>,
+
+    /// Cache raw API responses and 3D models in this directory
+    #[arg(long, value_name = "DIR")]
+    pub cache_dir: Option<PathBuf>,
+
+    /// Hours before cached component data is fetched again (3D models never expire)
+    #[arg(long, value_name = "HOURS", default_value = "168")]
+    pub cache_ttl: u64,
+
+    /// Ignore cached entries and fetch everything again (the cache is still updated)
+    #[arg(long, requires = "cache_dir", conflicts_with = "offline")]
+    pub refresh: bool,
+
+    /// Never contact EasyEDA; use only --cache-dir and --source-dir
+    #[arg(long)]
+    pub offline: bool,
+
+    /// Also write the raw API response, shape lists and parsed shapes as JSON to <DIR>/<ID>/
+    #[arg(long, value_name = "DIR")]
+    pub dump_raw: Option<PathBuf>,
+
+    /// KiCad release whose native file format is written
+    #[arg(long, value_name = "VERSION", value_enum, default_value = "6")]
+    pub kicad_version: KicadVersion,
+
+    /// Line segments used to draw non-circular ellipses in symbols (minimum 8)
+    #[arg(long, value_name = "N", default_value = "32")]
+    pub ellipse_segments: usize,
+
+    /// Maximum deviation in mm when flattening Bezier curves into line segments
+    #[arg(long, value_name = "MM", default_value = "0.01")]
+    pub curve_tolerance: f64,
+
+    /// Write Bezier curves as KiCad bezier/fp_curve primitives instead of line segments
+    #[arg(long)]
+    pub native_curves: bool,
+
+    /// IPC-7351 density level setting the footprint courtyard clearance, or none to skip it
+    #[arg(long, value_name = "LEVEL", value_enum, default_value = "nominal")]
+    pub courtyard: CourtyardDensity,
+
+    /// Also fit the courtyard around the body outline drawn from the footprint's SVG (3D outline) nodes
+    #[arg(long)]
+    pub courtyard_from_outline: bool,
+
+    /// Write generic chip resistors/capacitors as symbols extending Device:R/Device:C with stock KiCad footprints
+    #[arg(long)]
+    pub stock_passives: bool,
+
+    /// TOML table mapping EasyEDA c_para keys to symbol property names ("" drops a default)
+    #[arg(long, value_name = "FILE")]
+    pub property_map: Option<PathBuf>,
+
+    /// Export c_para KEY as hidden symbol property NAME (repeatable; NAME defaults to KEY)
+    #[arg(long = "property", value_name = "KEY=NAME")]
+    pub properties: Vec<String>,
+
+    /// TOML file with API endpoints and HTTP client settings
+    #[arg(long, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+
+    /// Base URL for component data (overrides the config file)
+    #[arg(long, value_name = "URL")]
+    pub api_base_url: Option<String>,
+
+    /// Base URL for 3D model downloads (overrides the config file)
+    #[arg(long, value_name = "URL")]
+    pub model_base_url: Option<String>,
+
+    /// Keyword search endpoint used by `nlbn search` (overrides the config file)
+    #[arg(long, value_name = "URL")]
+    pub search_url: Option<String>,
+
+    /// EasyEDA API version string sent with component requests
+    #[arg(long, value_name = "VERSION")]
+    pub api_version: Option<String>,
+
+    /// HTTP(S) proxy for all requests
+    #[arg(long, value_name = "URL")]
+    pub proxy: Option<String>,
+
+    /// Extra trusted CA certificate in PEM format (repeatable)
+    #[arg(long, value_name = "FILE")]
+    pub ca_cert: Vec<PathBuf>,
+
+    /// User agent sent with all requests
+    #[arg(long, value_name = "STRING")]
+    pub user_agent: Option<String>,
+
+    /// Connection timeout in seconds
+    #[arg(long, value_name = "SECS")]
+    pub connect_timeout: Option<u64>,
+
+    /// Read timeout in seconds
+    #[arg(long, value_name = "SECS")]
+    pub read_timeout: Option<u64>,
+
+    /// Attempts per request before giving up on 429/5xx responses and network errors
+    #[arg(long, value_name = "N")]
+    pub max_attempts: Option<u32>,
+
+    /// Maximum requests per second across all parallel workers (0 disables)
+    #[arg(long, value_name = "REQ_PER_SEC")]
+    pub rate_limit: Option<f64>,
+
+    /// `property_map()`, built once on first use
+    #[arg(skip)]
+    resolved_property_map: OnceLock<PropertyMap>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Search EasyEDA/LCSC by keyword or MPN and list matching LCSC IDs
+    Search(SearchArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct SearchArgs {
+    /// Keyword or manufacturer part number (e.g., STM32F103C8T6)
+    pub query: String,
+
+    /// Maximum number of parts to list
+    #[arg(long, default_value = "10")]
+    pub limit: usize,
+
+    /// Convert the top hit (everything unless --symbol, --footprint or --3d is given)
+    #[arg(long)]
+    pub convert_first: bool,
 }
 
 impl Cli {
@@ -83,19 +227,19 @@ impl Cli {
         }
 
         // Check if at least one ID source is provided
-        if self.lcsc_id.is_none() && self.batch.is_none() {
+        if self.lcsc_id.is_none() && self.batch.is_none() && self.from_file.is_none() {
             return Err(AppError::Other(
-                "Either --lcsc-id or --batch must be specified".to_string(),
+                "Either --lcsc-id, --batch or --from-file must be specified".to_string(),
             ));
         }
 
         // Validate LCSC ID format if provided
-        if let Some(ref id) = self.lcsc_id {
-            if !id.starts_with('C') || id.len() < 2 {
-                return Err(AppError::Easyeda(
-                    crate::error::EasyedaError::InvalidLcscId(id.clone()),
-                ));
-            }
+        if let Some(ref id) = self.lcsc_id
+            && (!id.starts_with('C') || id.len() < 2)
+        {
+            return Err(AppError::Easyeda(
+                crate::error::EasyedaError::InvalidLcscId(id.clone()),
+            ));
         }
 
         // Check if at least one conversion option is selected
@@ -105,34 +249,58 @@ impl Cli {
             ));
         }
 
-        if let Some(lib_name) = &self.lib_name {
-            if lib_name.trim().is_empty() {
-                return Err(AppError::Other("--lib-name must not be empty".to_string()));
-            }
+        if let Some(lib_name) = &self.lib_name
+            && lib_name.trim().is_empty()
+        {
+            return Err(AppError::Other("--lib-name must not be empty".to_string()));
         }
 
-        if let Some(symbol_lib) = &self.symbol_lib {
-            if !path_ends_with(symbol_lib, ".kicad_sym") {
-                return Err(AppError::Other(
-                    "--symbol-lib must point to a .kicad_sym file".to_string(),
-                ));
-            }
+        // KiCad 5 uses legacy .lib symbol libraries
+        let symbol_extension = if self.kicad_version == KicadVersion::V5 {
+            ".lib"
+        } else {
+            ".kicad_sym"
+        };
+        if let Some(symbol_lib) = &self.symbol_lib
+            && !path_ends_with(symbol_lib, symbol_extension)
+        {
+            return Err(AppError::Other(format!(
+                "--symbol-lib must point to a {} file",
+                symbol_extension
+            )));
         }
 
-        if let Some(footprint_lib) = &self.footprint_lib {
-            if !path_ends_with(footprint_lib, ".pretty") {
-                return Err(AppError::Other(
-                    "--footprint-lib must point to a .pretty directory".to_string(),
-                ));
-            }
+        if let Some(footprint_lib) = &self.footprint_lib
+            && !path_ends_with(footprint_lib, ".pretty")
+        {
+            return Err(AppError::Other(
+                "--footprint-lib must point to a .pretty directory".to_string(),
+            ));
         }
 
-        if let Some(model_lib) = &self.model_lib {
-            if !path_ends_with(model_lib, ".3dshapes") {
-                return Err(AppError::Other(
-                    "--model-lib must point to a .3dshapes directory".to_string(),
-                ));
-            }
+        if self.offline && self.cache_dir.is_none() && self.source_dir.is_none() {
+            return Err(AppError::Other(
+                "--offline requires --cache-dir or --source-dir".to_string(),
+            ));
+        }
+
+        if let Some(source_dir) = &self.source_dir
+            && !source_dir.is_dir()
+        {
+            return Err(AppError::Other(format!(
+                "--source-dir must point to an existing directory: {}",
+                source_dir.display()
+            )));
+        }
+
+        self.property_map()?;
+
+        if let Some(model_lib) = &self.model_lib
+            && !path_ends_with(model_lib, ".3dshapes")
+        {
+            return Err(AppError::Other(
+                "--model-lib must point to a .3dshapes directory".to_string(),
+            ));
         }
 
         Ok(())
@@ -170,8 +338,77 @@ impl Cli {
         }
     }
 
+    /// API settings from --config, with individual command-line options taking precedence
+    pub fn api_config(&self) -> Result<EasyedaApiConfig> {
+        let mut config = match &self.config {
+            Some(path) => EasyedaApiConfig::from_file(path)?,
+            None => EasyedaApiConfig::default(),
+        };
+
+        if let Some(url) = &self.api_base_url {
+            config = config.api_base_url(url);
+        }
+        if let Some(url) = &self.model_base_url {
+            config = config.model_base_url(url);
+        }
+        if let Some(url) = &self.search_url {
+            config = config.search_url(url);
+        }
+        if let Some(version) = &self.api_version {
+            config = config.api_version(version);
+        }
+        if let Some(proxy) = &self.proxy {
+            config = config.proxy(proxy);
+        }
+        for path in &self.ca_cert {
+            config = config.ca_cert(path);
+        }
+        if let Some(user_agent) = &self.user_agent {
+            config = config.user_agent(user_agent);
+        }
+        if let Some(secs) = self.connect_timeout {
+            config = config.connect_timeout(Duration::from_secs(secs));
+        }
+        if let Some(secs) = self.read_timeout {
+            config = config.read_timeout(Duration::from_secs(secs));
+        }
+        if let Some(attempts) = self.max_attempts {
+            config = config.max_attempts(attempts);
+        }
+        if let Some(rate) = self.rate_limit {
+            config = config.rate_limit(rate);
+        }
+
+        Ok(config)
+    }
+
+    /// Default c_para mapping, overlaid with --property-map and then --property.
+    /// The map file is read on the first call only.
+    pub fn property_map(&self) -> Result<&PropertyMap> {
+        if let Some(map) = self.resolved_property_map.get() {
+            return Ok(map);
+        }
+
+        let mut map = match &self.property_map {
+            Some(path) => PropertyMap::from_file(path)?,
+            None => PropertyMap::default(),
+        };
+        for arg in &self.properties {
+            map.set_from_arg(arg)?;
+        }
+        Ok(self.resolved_property_map.get_or_init(|| map))
+    }
+
+    pub fn curve_options(&self) -> CurveOptions {
+        CurveOptions {
+            // 1 px = 0.254 mm
+            tolerance: self.curve_tolerance.max(0.0001) / 0.254,
+            native: self.native_curves,
+        }
+    }
+
     pub fn kicad_version(&self) -> KicadVersion {
-        KicadVersion::V6
+        self.kicad_version
     }
 
     pub fn resolved_lib_name(&self) -> String {
@@ -199,9 +436,24 @@ nlbn --full --lcsc-id C2040
 # Convert only the symbol
 nlbn --symbol --lcsc-id C2040
 
+# Look up LCSC IDs by part number
+nlbn search STM32F103C8T6
+
+# Convert the best search match straight away
+nlbn search STM32F103C8T6 --convert-first -o ./kicad-libs
+
+# Convert a symbol/footprint exported from the EasyEDA editor
+nlbn --full --from-file MyPart.json
+
+# Export extra c_para parameters as symbol properties
+nlbn --symbol --lcsc-id C25804 --property Resistance --property "BOM_Manufacturer Part=MPN"
+
 # Batch convert from a file
 nlbn --full --batch components.txt --parallel 8
 
+# Cache downloads so later runs need no network traffic
+nlbn --full --batch components.txt --cache-dir ./.nlbn-cache
+
 # Append into an existing library set under one output directory
 nlbn --full --lcsc-id C2040 -o ./kicad-libs --lib-name MyParts
 
@@ -214,10 +466,75 @@ nlbn --full --lcsc-id C2040 --symbol-lib ./kicad/MyParts.kicad_sym --footprint-l
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Target KiCad release; later variants compare greater
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
 pub enum KicadVersion {
+    #[value(name = "5")]
     V5,
+    #[value(name = "6")]
     V6,
+    #[value(name = "7")]
+    V7,
+    #[value(name = "8")]
+    V8,
+    #[value(name = "9")]
+    V9,
+}
+
+impl KicadVersion {
+    /// `(version ...)` date code of `.kicad_sym` files
+    pub fn symbol_format(&self) -> u32 {
+        match self {
+            KicadVersion::V5 | KicadVersion::V6 => 20211014,
+            KicadVersion::V7 => 20220914,
+            KicadVersion::V8 => 20231120,
+            KicadVersion::V9 => 20241209,
+        }
+    }
+
+    /// `(version ...)` date code of `.kicad_mod` files
+    pub fn footprint_format(&self) -> u32 {
+        match self {
+            KicadVersion::V5 | KicadVersion::V6 => 20211014,
+            KicadVersion::V7 => 20221018,
+            KicadVersion::V8 => 20240108,
+            KicadVersion::V9 => 20241229,
+        }
+    }
+
+    /// `(generator_version ...)`, written since KiCad 8
+    pub fn generator_version(&self) -> Option<&'static str> {
+        match self {
+            KicadVersion::V8 => Some("8.0"),
+            KicadVersion::V9 => Some("9.0"),
+            _ => None,
+        }
+    }
+}
+
+/// IPC-7351 density level of generated courtyards
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CourtyardDensity {
+    /// No courtyard is generated
+    None,
+    /// Level C, 0.1 mm around pads and body
+    Least,
+    /// Level B, 0.25 mm around pads and body
+    Nominal,
+    /// Level A, 0.5 mm around pads and body
+    Most,
+}
+
+impl CourtyardDensity {
+    /// Courtyard excess around pads and body in mm
+    pub fn clearance(&self) -> Option<f64> {
+        match self {
+            CourtyardDensity::None => None,
+            CourtyardDensity::Least => Some(0.1),
+            CourtyardDensity::Nominal => Some(0.25),
+            CourtyardDensity::Most => Some(0.5),
+        }
+    }
 }
 
 fn path_ends_with(path: &std::path::Path, suffix: &str) -> bool {
@@ -280,6 +597,89 @@ mod tests {
         assert!(err.contains("--model-lib must point to a .3dshapes directory"));
     }
 
+    #[test]
+    fn rejects_offline_without_local_data() {
+        let cli =
+            Cli::try_parse_from(["nlbn", "--lcsc-id", "C2040", "--full", "--offline"]).unwrap();
+
+        let err = cli.validate().unwrap_err().to_string();
+        assert!(err.contains("--offline requires --cache-dir or --source-dir"));
+    }
+
+    #[test]
+    fn command_line_overrides_api_defaults() {
+        let cli = Cli::try_parse_from([
+            "nlbn",
+            "--lcsc-id",
+            "C2040",
+            "--full",
+            "--api-base-url",
+            "http://localhost:9000",
+            "--read-timeout",
+            "90",
+            "--rate-limit",
+            "2.5",
+        ])
+        .unwrap();
+
+        let config = cli.api_config().unwrap();
+        assert_eq!(config.api_base_url, "http://localhost:9000");
+        assert_eq!(config.read_timeout_secs, 90);
+        assert_eq!(config.connect_timeout_secs, 5);
+        assert_eq!(config.rate_limit, 2.5);
+        assert_eq!(config.max_attempts, 3);
+    }
+
+    #[test]
+    fn parses_search_subcommand_with_conversion_flags() {
+        let cli = Cli::try_parse_from([
+            "nlbn",
+            "search",
+            "STM32F103C8T6",
+            "--convert-first",
+            "--symbol",
+            "-o",
+            "libs",
+        ])
+        .unwrap();
+
+        match cli.command {
+            Some(Command::Search(ref search)) => {
+                assert_eq!(search.query, "STM32F103C8T6");
+                assert_eq!(search.limit, 10);
+                assert!(search.convert_first);
+            }
+            None => panic!("expected search subcommand"),
+        }
+        assert!(cli.symbol);
+        assert_eq!(cli.output, PathBuf::from("libs"));
+    }
+
+    #[test]
+    fn property_map_file_is_read_once() {
+        let dir = crate::easyeda::source::temp_dir("property-map");
+        let path = dir.join("map.toml");
+        std::fs::write(&path, "Tolerance = \"Tol\"\n").unwrap();
+
+        let cli = Cli::try_parse_from([
+            "nlbn",
+            "--lcsc-id",
+            "C2040",
+            "--symbol",
+            "--property-map",
+            path.to_str().unwrap(),
+        ])
+        .unwrap();
+        cli.validate().unwrap();
+
+        // Conversions reuse the map parsed during validation
+        std::fs::remove_file(&path).unwrap();
+        let first = cli.property_map().unwrap();
+        assert!(std::ptr::eq(first, cli.property_map().unwrap()));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
     #[test]
     fn prompt_mode_skips_conversion_validation() {
         let cli = Cli::try_parse_from(["nlbn", "--prompt"]).unwrap();
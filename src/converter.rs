@@ -1,6 +1,15 @@
 use crate::cli::KicadVersion;
 use crate::error::{ConversionError, Result};
 
+/// How Bezier segments of symbol and footprint outlines are written
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CurveOptions {
+    /// Maximum distance between a curve and its flattened segments, in px
+    pub tolerance: f64,
+    /// Emit KiCad `bezier`/`fp_curve` primitives instead of flattening
+    pub native: bool,
+}
+
 pub struct Converter {
     _kicad_version: KicadVersion,
 }
@@ -14,7 +23,7 @@ impl Converter {
 
     /// Convert pixels to mils (1 px = 10 mils in EasyEDA)
     pub fn px_to_mil(&self, px: f64) -> i32 {
-        (10.0 * px) as i32
+        (10.0 * px).round() as i32
     }
 
     /// Convert pixels to millimeters (1 px = 10 mils = 0.254 mm)
@@ -22,6 +31,11 @@ impl Converter {
         10.0 * px * 0.0254
     }
 
+    /// Convert millimeters to pixels
+    pub fn mm_to_px(&self, mm: f64) -> f64 {
+        mm / 0.254
+    }
+
     /// Flip Y coordinate (EasyEDA uses top-left origin, KiCad uses bottom-left)
     pub fn flip_y(&self, y: f64) -> f64 {
         -y
@@ -153,6 +167,37 @@ impl Converter {
         Ok((cx, cy, theta1, theta2))
     }
 
+    /// Resolve an SVG arc segment for flattening. Radii too small to reach
+    /// `end` are scaled up as the SVG spec requires.
+    pub fn svg_arc(
+        &self,
+        start: (f64, f64),
+        end: (f64, f64),
+        radii: (f64, f64),
+        x_axis_rotation: f64,
+        large_arc: bool,
+        sweep: bool,
+    ) -> Result<SvgArc> {
+        let (cx, cy, start_angle, end_angle) =
+            self.compute_arc_center(start, end, radii, x_axis_rotation, large_arc, sweep)?;
+        let mut extent = (end_angle - start_angle).rem_euclid(360.0);
+        if !sweep && extent > 0.0 {
+            extent -= 360.0;
+        }
+        // The angles are measured on the unrotated ellipse, so is the scale
+        let (sin, cos) = self.deg_to_rad(x_axis_rotation).sin_cos();
+        let (dx, dy) = (start.0 - cx, start.1 - cy);
+        let (rx, ry) = radii;
+        let scale = ((cos * dx + sin * dy) / rx).hypot((cos * dy - sin * dx) / ry);
+        Ok(SvgArc {
+            center: (cx, cy),
+            radii: (rx.abs() * scale, ry.abs() * scale),
+            rotation: x_axis_rotation,
+            start_angle,
+            extent,
+        })
+    }
+
     /// Calculate bounding box for a set of points
     pub fn calculate_bbox(&self, points: &[(f64, f64)]) -> Option<(f64, f64, f64, f64)> {
         if points.is_empty() {
@@ -175,6 +220,186 @@ impl Converter {
     }
 }
 
+/// SVG arc segment in center form; angles in degrees, `extent` signed.
+/// `start_angle` is measured on the ellipse before its `rotation`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SvgArc {
+    pub center: (f64, f64),
+    pub radii: (f64, f64),
+    pub rotation: f64,
+    pub start_angle: f64,
+    pub extent: f64,
+}
+
+impl SvgArc {
+    /// Points within `tolerance` of the arc, excluding its start
+    pub fn flatten(&self, tolerance: f64) -> Vec<(f64, f64)> {
+        let (sin, cos) = self.rotation.to_radians().sin_cos();
+        flatten_arc(
+            (0.0, 0.0),
+            self.radii,
+            self.start_angle,
+            self.extent,
+            tolerance,
+        )
+        .into_iter()
+        .map(|(x, y)| {
+            (
+                self.center.0 + cos * x - sin * y,
+                self.center.1 + sin * x + cos * y,
+            )
+        })
+        .collect()
+    }
+}
+
+/// Points approximating a cubic Bezier, excluding `p0` and ending at `p3`.
+///
+/// Segments are split until the control points are within `tolerance`
+/// of the chord, so flat curves need few points and tight ones get more.
+pub fn flatten_cubic(
+    p0: (f64, f64),
+    p1: (f64, f64),
+    p2: (f64, f64),
+    p3: (f64, f64),
+    tolerance: f64,
+) -> Vec<(f64, f64)> {
+    let mut points = Vec::new();
+    flatten_cubic_into(p0, p1, p2, p3, tolerance.max(1e-6), 0, &mut points);
+    points
+}
+
+/// Points approximating a quadratic Bezier, excluding `p0` and ending at `p2`
+pub fn flatten_quad(
+    p0: (f64, f64),
+    p1: (f64, f64),
+    p2: (f64, f64),
+    tolerance: f64,
+) -> Vec<(f64, f64)> {
+    let (c1, c2) = quad_to_cubic(p0, p1, p2);
+    flatten_cubic(p0, c1, c2, p2, tolerance)
+}
+
+/// Points approximating an elliptical arc, excluding the start and ending at
+/// `start_angle + sweep` (degrees; positive sweeps run towards increasing
+/// angles). Each segment stays within `tolerance` of the arc.
+pub fn flatten_arc(
+    center: (f64, f64),
+    radii: (f64, f64),
+    start_angle: f64,
+    sweep: f64,
+    tolerance: f64,
+) -> Vec<(f64, f64)> {
+    let radius = radii.0.abs().max(radii.1.abs());
+    let max_step = if radius > tolerance {
+        2.0 * (1.0 - tolerance.max(1e-6) / radius).acos().to_degrees()
+    } else {
+        90.0
+    };
+    let segments = (sweep.abs() / max_step.clamp(1.0, 90.0)).ceil().max(1.0) as usize;
+
+    (1..=segments)
+        .map(|i| {
+            let angle = (start_angle + sweep * i as f64 / segments as f64).to_radians();
+            (
+                center.0 + radii.0 * angle.cos(),
+                center.1 + radii.1 * angle.sin(),
+            )
+        })
+        .collect()
+}
+
+/// Control points of the cubic Bezier equal to a quadratic one
+pub fn quad_to_cubic(p0: (f64, f64), p1: (f64, f64), p2: (f64, f64)) -> ((f64, f64), (f64, f64)) {
+    (
+        (
+            p0.0 + 2.0 / 3.0 * (p1.0 - p0.0),
+            p0.1 + 2.0 / 3.0 * (p1.1 - p0.1),
+        ),
+        (
+            p2.0 + 2.0 / 3.0 * (p1.0 - p2.0),
+            p2.1 + 2.0 / 3.0 * (p1.1 - p2.1),
+        ),
+    )
+}
+
+fn flatten_cubic_into(
+    p0: (f64, f64),
+    p1: (f64, f64),
+    p2: (f64, f64),
+    p3: (f64, f64),
+    tolerance: f64,
+    depth: u32,
+    points: &mut Vec<(f64, f64)>,
+) {
+    // Distance of both control points from the chord p0-p3
+    let (dx, dy) = (p3.0 - p0.0, p3.1 - p0.1);
+    let chord = dx.hypot(dy);
+    let distance = |p: (f64, f64)| {
+        if chord < 1e-12 {
+            (p.0 - p0.0).hypot(p.1 - p0.1)
+        } else {
+            ((p.0 - p0.0) * dy - (p.1 - p0.1) * dx).abs() / chord
+        }
+    };
+
+    if depth >= 16 || distance(p1).max(distance(p2)) <= tolerance {
+        points.push(p3);
+        return;
+    }
+
+    // de Casteljau split at t = 0.5
+    let mid = |a: (f64, f64), b: (f64, f64)| ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0);
+    let p01 = mid(p0, p1);
+    let p12 = mid(p1, p2);
+    let p23 = mid(p2, p3);
+    let p012 = mid(p01, p12);
+    let p123 = mid(p12, p23);
+    let center = mid(p012, p123);
+
+    flatten_cubic_into(p0, p01, p012, center, tolerance, depth + 1, points);
+    flatten_cubic_into(center, p123, p23, p3, tolerance, depth + 1, points);
+}
+
+/// Circle through the start, mid and end point of an arc.
+///
+/// Returns the center, the angle of `start` seen from the center and the
+/// signed sweep to `end` through `mid`, all in degrees; a positive sweep
+/// runs towards increasing `atan2` angles. `None` for collinear points.
+pub fn arc_through(
+    start: (f64, f64),
+    mid: (f64, f64),
+    end: (f64, f64),
+) -> Option<((f64, f64), f64, f64)> {
+    let (ax, ay) = start;
+    let (bx, by) = mid;
+    let (cx, cy) = end;
+    let d = 2.0 * (ax * (by - cy) + bx * (cy - ay) + cx * (ay - by));
+    if d.abs() < 1e-12 {
+        return None;
+    }
+
+    let a2 = ax * ax + ay * ay;
+    let b2 = bx * bx + by * by;
+    let c2 = cx * cx + cy * cy;
+    let center = (
+        (a2 * (by - cy) + b2 * (cy - ay) + c2 * (ay - by)) / d,
+        (a2 * (cx - bx) + b2 * (ax - cx) + c2 * (bx - ax)) / d,
+    );
+
+    let angle = |(x, y): (f64, f64)| (y - center.1).atan2(x - center.0).to_degrees();
+    let start_angle = angle(start);
+    let to_mid = (angle(mid) - start_angle).rem_euclid(360.0);
+    let to_end = (angle(end) - start_angle).rem_euclid(360.0);
+    let sweep = if to_mid <= to_end {
+        to_end
+    } else {
+        to_end - 360.0
+    };
+
+    Some((center, start_angle, sweep))
+}
+
 /// Sanitize a component name for use as a filename/identifier
 pub fn sanitize_name(name: &str) -> String {
     name.chars()
@@ -202,6 +427,53 @@ pub fn angle_to_ki(rotation: f64) -> f64 {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_arc_through() {
+        // Upper half of the unit circle, counter-clockwise in y-up coordinates
+        let (center, start, sweep) = arc_through((1.0, 0.0), (0.0, 1.0), (-1.0, 0.0)).unwrap();
+        assert!(center.0.abs() < 1e-9 && center.1.abs() < 1e-9);
+        assert!(start.abs() < 1e-9);
+        assert!((sweep - 180.0).abs() < 1e-9);
+
+        // Same end points through the lower half
+        let (_, _, sweep) = arc_through((1.0, 0.0), (0.0, -1.0), (-1.0, 0.0)).unwrap();
+        assert!((sweep + 180.0).abs() < 1e-9);
+
+        assert!(arc_through((0.0, 0.0), (1.0, 1.0), (2.0, 2.0)).is_none());
+    }
+
+    #[test]
+    fn svg_arc_honors_x_axis_rotation() {
+        let converter = Converter::new(KicadVersion::V6);
+        let close = |a: (f64, f64), b: (f64, f64)| (a.0 - b.0).hypot(a.1 - b.1) < 1e-9;
+
+        // Rotating a circle changes nothing
+        let arc = converter
+            .svg_arc((0.0, 0.0), (20.0, 0.0), (10.0, 10.0), 90.0, false, true)
+            .unwrap();
+        let points = arc.flatten(0.01);
+        assert!(close(*points.last().unwrap(), (20.0, 0.0)));
+        assert!(
+            points
+                .iter()
+                .all(|&p| ((p.0 - 10.0).hypot(p.1) - 10.0).abs() < 1e-9)
+        );
+
+        // Ellipse with its long axis vertical, stretched to reach (20, 0)
+        let arc = converter
+            .svg_arc((0.0, 0.0), (20.0, 0.0), (20.0, 5.0), 90.0, false, true)
+            .unwrap();
+        let points = arc.flatten(0.01);
+        assert!(close(*points.last().unwrap(), (20.0, 0.0)));
+        let (rx, ry) = arc.radii;
+        assert!((rx - 40.0).abs() < 1e-9 && (ry - 10.0).abs() < 1e-9);
+        // On the rotated ellipse the short axis is horizontal
+        for &(x, y) in &points {
+            let (u, v) = (y - arc.center.1, -(x - arc.center.0));
+            assert!(((u / rx).powi(2) + (v / ry).powi(2) - 1.0).abs() < 1e-9);
+        }
+    }
+
     #[test]
     fn test_px_to_mil() {
         let converter = Converter::new(KicadVersion::V6);
@@ -223,6 +495,24 @@ mod tests {
         assert_eq!(converter.flip_y(-5.0), 5.0);
     }
 
+    #[test]
+    fn test_flatten_cubic_within_tolerance() {
+        let (p0, p1, p2, p3) = ((0.0, 0.0), (0.0, 10.0), (10.0, 10.0), (10.0, 0.0));
+
+        let coarse = flatten_cubic(p0, p1, p2, p3, 1.0);
+        let fine = flatten_cubic(p0, p1, p2, p3, 0.01);
+        assert!(fine.len() > coarse.len());
+        assert_eq!(*fine.last().unwrap(), p3);
+
+        // Apex of this symmetric curve is at (5, 7.5)
+        let apex = fine.iter().map(|p| p.1).fold(f64::MIN, f64::max);
+        assert!((apex - 7.5).abs() < 0.02);
+
+        // A straight "curve" needs no intermediate points
+        let line = flatten_quad((0.0, 0.0), (5.0, 0.0), (10.0, 0.0), 0.01);
+        assert_eq!(line, vec![(10.0, 0.0)]);
+    }
+
     #[test]
     fn test_deg_to_rad() {
         let converter = Converter::new(KicadVersion::V6);
@@ -0,0 +1,408 @@
+use crate::cli::CourtyardDensity;
+use crate::converter::{Converter, arc_through};
+use crate::kicad;
+use std::collections::HashMap;
+
+/// Courtyard layer
+const LAYER: &str = "F.CrtYd";
+
+/// Courtyard line width in mm, as in KiCad's own libraries
+const LINE_WIDTH: f64 = 0.05;
+
+/// Courtyard corners are snapped outwards to this grid, in mm
+const GRID: f64 = 0.01;
+
+/// Axis-aligned box
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Bounds {
+    min_x: f64,
+    min_y: f64,
+    max_x: f64,
+    max_y: f64,
+}
+
+impl Bounds {
+    fn around(points: impl IntoIterator<Item = (f64, f64)>) -> Option<Self> {
+        points.into_iter().fold(None, |bounds, (x, y)| {
+            Some(match bounds {
+                None => Bounds {
+                    min_x: x,
+                    min_y: y,
+                    max_x: x,
+                    max_y: y,
+                },
+                Some(b) => Bounds {
+                    min_x: b.min_x.min(x),
+                    min_y: b.min_y.min(y),
+                    max_x: b.max_x.max(x),
+                    max_y: b.max_y.max(y),
+                },
+            })
+        })
+    }
+
+    fn union(&self, other: &Bounds) -> Self {
+        Bounds {
+            min_x: self.min_x.min(other.min_x),
+            min_y: self.min_y.min(other.min_y),
+            max_x: self.max_x.max(other.max_x),
+            max_y: self.max_y.max(other.max_y),
+        }
+    }
+
+    /// Grown by `margin` on every side and snapped outwards to the grid
+    fn expand(&self, margin: f64) -> Self {
+        // The epsilon keeps values already on the grid where they are
+        let down = |v: f64| ((v - margin) / GRID + 1e-6).floor() * GRID;
+        let up = |v: f64| ((v + margin) / GRID - 1e-6).ceil() * GRID;
+        Bounds {
+            min_x: down(self.min_x),
+            min_y: down(self.min_y),
+            max_x: up(self.max_x),
+            max_y: up(self.max_y),
+        }
+    }
+
+    fn contains(&self, other: &Bounds) -> bool {
+        self.min_x <= other.min_x
+            && self.min_y <= other.min_y
+            && self.max_x >= other.max_x
+            && self.max_y >= other.max_y
+    }
+
+    /// Whether the two boxes share some area, not just an edge or a corner
+    fn overlaps(&self, other: &Bounds) -> bool {
+        self.min_x < other.max_x
+            && other.min_x < self.max_x
+            && self.min_y < other.max_y
+            && other.min_y < self.max_y
+    }
+}
+
+/// Courtyard shape in mm
+enum Outline {
+    Rect(Bounds),
+    Polygon(Vec<(f64, f64)>),
+}
+
+/// Add an IPC-7351 courtyard on `F.CrtYd` around the pads and the body.
+///
+/// The body is the outline drawn on `F.Fab`, or on `F.SilkS` when the
+/// footprint has no fabrication drawing. Pads and body each get the
+/// clearance of the density level and are snapped to a 0.01 mm grid; the
+/// courtyard is an `fp_rect` when one area covers the other, and an
+/// `fp_poly` around both where pads stick out of the body.
+/// Footprints that already have a courtyard are left alone.
+pub fn add_courtyard(
+    footprint: &mut kicad::KiFootprint,
+    density: CourtyardDensity,
+    converter: &Converter,
+) {
+    let Some(clearance) = density.clearance() else {
+        return;
+    };
+    if has_courtyard(footprint) {
+        return;
+    }
+
+    let areas: Vec<Bounds> = [pad_bounds(footprint), body_bounds(footprint)]
+        .into_iter()
+        .flatten()
+        .map(|b| {
+            let mm = Bounds {
+                min_x: converter.px_to_mm(b.min_x),
+                min_y: converter.px_to_mm(b.min_y),
+                max_x: converter.px_to_mm(b.max_x),
+                max_y: converter.px_to_mm(b.max_y),
+            };
+            mm.expand(clearance)
+        })
+        .collect();
+
+    let outline = match areas[..] {
+        [] => return,
+        [area] => Outline::Rect(area),
+        [pads, body] if body.contains(&pads) => Outline::Rect(body),
+        [pads, body] if pads.contains(&body) => Outline::Rect(pads),
+        [pads, body] if pads.overlaps(&body) => Outline::Polygon(union_outline(&pads, &body)),
+        // Apart from each other: one box around both
+        [pads, body] => Outline::Rect(pads.union(&body)),
+        _ => unreachable!("at most pads and body"),
+    };
+
+    let width = converter.mm_to_px(LINE_WIDTH);
+    match outline {
+        Outline::Rect(area) => footprint.rects.push(kicad::KiRect {
+            start_x: converter.mm_to_px(area.min_x),
+            start_y: converter.mm_to_px(area.min_y),
+            end_x: converter.mm_to_px(area.max_x),
+            end_y: converter.mm_to_px(area.max_y),
+            width,
+            layer: LAYER.to_string(),
+            fill: false,
+        }),
+        Outline::Polygon(points) => footprint.polygons.push(kicad::KiPolygon {
+            points: points
+                .into_iter()
+                .map(|(x, y)| (converter.mm_to_px(x), converter.mm_to_px(y)))
+                .collect(),
+            width,
+            layer: LAYER.to_string(),
+            fill: false,
+        }),
+    }
+}
+
+fn has_courtyard(footprint: &kicad::KiFootprint) -> bool {
+    let on_courtyard = |layer: &str| layer.ends_with(".CrtYd");
+    footprint.lines.iter().any(|l| on_courtyard(&l.layer))
+        || footprint.arcs.iter().any(|a| on_courtyard(&a.layer))
+        || footprint.circles.iter().any(|c| on_courtyard(&c.layer))
+        || footprint.curves.iter().any(|c| on_courtyard(&c.layer))
+        || footprint.rects.iter().any(|r| on_courtyard(&r.layer))
+        || footprint.polygons.iter().any(|p| on_courtyard(&p.layer))
+}
+
+/// Extent of all pads and copper areas in px, including rotation and custom outlines
+fn pad_bounds(footprint: &kicad::KiFootprint) -> Option<Bounds> {
+    let copper = footprint
+        .polygons
+        .iter()
+        .filter(|p| p.layer.ends_with(".Cu"))
+        .flat_map(|p| p.points.iter().copied());
+    let points = footprint.pads.iter().flat_map(|pad| {
+        let (sin, cos) = pad.rotation.to_radians().sin_cos();
+        let half_x = (pad.size_x * cos.abs() + pad.size_y * sin.abs()) / 2.0;
+        let half_y = (pad.size_x * sin.abs() + pad.size_y * cos.abs()) / 2.0;
+
+        let mut points = vec![
+            (pad.pos_x - half_x, pad.pos_y - half_y),
+            (pad.pos_x + half_x, pad.pos_y + half_y),
+        ];
+        if let Some(polygon) = &pad.polygon {
+            points.extend(polygon.iter().map(|(x, y)| (pad.pos_x + x, pad.pos_y + y)));
+        }
+        points
+    });
+    Bounds::around(points.chain(copper))
+}
+
+/// Extent of the body outline in px: `F.Fab`, falling back to `F.SilkS`
+fn body_bounds(footprint: &kicad::KiFootprint) -> Option<Bounds> {
+    ["F.Fab", "F.SilkS"]
+        .into_iter()
+        .find_map(|layer| Bounds::around(outline_points(footprint, layer)))
+}
+
+/// Points whose bounding box is that of the graphics on `layer`
+fn outline_points(footprint: &kicad::KiFootprint, layer: &str) -> Vec<(f64, f64)> {
+    let mut points = Vec::new();
+
+    for line in footprint.lines.iter().filter(|l| l.layer == layer) {
+        points.push((line.start_x, line.start_y));
+        points.push((line.end_x, line.end_y));
+    }
+    for circle in footprint.circles.iter().filter(|c| c.layer == layer) {
+        let radius = (circle.end_x - circle.center_x).hypot(circle.end_y - circle.center_y);
+        points.push((circle.center_x - radius, circle.center_y - radius));
+        points.push((circle.center_x + radius, circle.center_y + radius));
+    }
+    for arc in footprint.arcs.iter().filter(|a| a.layer == layer) {
+        points.extend(arc_extremes(arc));
+    }
+    // A Bezier curve lies within the hull of its control points
+    for curve in footprint.curves.iter().filter(|c| c.layer == layer) {
+        points.extend(curve.points);
+    }
+    for rect in footprint.rects.iter().filter(|r| r.layer == layer) {
+        points.push((rect.start_x, rect.start_y));
+        points.push((rect.end_x, rect.end_y));
+    }
+    for polygon in footprint.polygons.iter().filter(|p| p.layer == layer) {
+        points.extend(&polygon.points);
+    }
+
+    points
+}
+
+/// End points of an arc and the points where it crosses the axes through its center
+fn arc_extremes(arc: &kicad::FootprintKiArc) -> Vec<(f64, f64)> {
+    let start = (arc.start_x, arc.start_y);
+    let mid = (arc.mid_x, arc.mid_y);
+    let end = (arc.end_x, arc.end_y);
+    let mut points = vec![start, mid, end];
+
+    if let Some((center, start_angle, sweep)) = arc_through(start, mid, end) {
+        let radius = (start.0 - center.0).hypot(start.1 - center.1);
+        for angle in [0.0_f64, 90.0, 180.0, 270.0] {
+            let offset = if sweep >= 0.0 {
+                (angle - start_angle).rem_euclid(360.0)
+            } else {
+                (start_angle - angle).rem_euclid(360.0)
+            };
+            if offset <= sweep.abs() {
+                let (sin, cos) = angle.to_radians().sin_cos();
+                points.push((center.0 + radius * cos, center.1 + radius * sin));
+            }
+        }
+    }
+
+    points
+}
+
+/// Outline of two overlapping boxes, walked along the edges of the grid
+/// their sides span; collinear corners are dropped
+fn union_outline(a: &Bounds, b: &Bounds) -> Vec<(f64, f64)> {
+    let mut xs = vec![a.min_x, a.max_x, b.min_x, b.max_x];
+    let mut ys = vec![a.min_y, a.max_y, b.min_y, b.max_y];
+    for values in [&mut xs, &mut ys] {
+        values.sort_by(f64::total_cmp);
+        values.dedup();
+    }
+
+    let filled = |i: isize, j: isize| -> bool {
+        if i < 0 || j < 0 || i as usize + 1 >= xs.len() || j as usize + 1 >= ys.len() {
+            return false;
+        }
+        let center = (
+            (xs[i as usize] + xs[i as usize + 1]) / 2.0,
+            (ys[j as usize] + ys[j as usize + 1]) / 2.0,
+        );
+        [a, b].iter().any(|r| {
+            r.min_x < center.0 && center.0 < r.max_x && r.min_y < center.1 && center.1 < r.max_y
+        })
+    };
+
+    // Boundary edges of the filled cells, clockwise on screen, keyed by start corner
+    let mut next: HashMap<(isize, isize), (isize, isize)> = HashMap::new();
+    for i in 0..xs.len() as isize - 1 {
+        for j in 0..ys.len() as isize - 1 {
+            if !filled(i, j) {
+                continue;
+            }
+            if !filled(i, j - 1) {
+                next.insert((i, j), (i + 1, j));
+            }
+            if !filled(i + 1, j) {
+                next.insert((i + 1, j), (i + 1, j + 1));
+            }
+            if !filled(i, j + 1) {
+                next.insert((i + 1, j + 1), (i, j + 1));
+            }
+            if !filled(i - 1, j) {
+                next.insert((i, j + 1), (i, j));
+            }
+        }
+    }
+
+    // Start at the top-left corner, which always is a real corner
+    let Some(&first) = next.keys().min_by_key(|&&(i, j)| (j, i)) else {
+        return Vec::new();
+    };
+    let mut corners = vec![first];
+    let mut current = next[&first];
+    while current != first && corners.len() <= next.len() {
+        corners.push(current);
+        current = next[&current];
+    }
+
+    let n = corners.len();
+    (0..n)
+        .filter(|&k| {
+            let (prev, here, after) = (corners[(k + n - 1) % n], corners[k], corners[(k + 1) % n]);
+            let d1 = (here.0 - prev.0, here.1 - prev.1);
+            let d2 = (after.0 - here.0, after.1 - here.1);
+            d1.0 * d2.1 - d1.1 * d2.0 != 0
+        })
+        .map(|k| (xs[corners[k].0 as usize], ys[corners[k].1 as usize]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::KicadVersion;
+
+    fn footprint() -> kicad::KiFootprint {
+        kicad::KiFootprint {
+            name: "SOT-23".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn pad(x: f64, y: f64, size_x: f64, size_y: f64, rotation: f64) -> kicad::KiPad {
+        kicad::KiPad {
+            number: "1".to_string(),
+            pad_type: kicad::PadType::Smd,
+            shape: kicad::PadShape::Rect,
+            pos_x: x,
+            pos_y: y,
+            size_x,
+            size_y,
+            rotation,
+            layers: vec!["F.Cu".to_string()],
+            drill: None,
+            polygon: None,
+            corner_ratio: 0.0,
+            chamfer: None,
+        }
+    }
+
+    fn fab_line(start: (f64, f64), end: (f64, f64)) -> kicad::KiLine {
+        kicad::KiLine {
+            start_x: start.0,
+            start_y: start.1,
+            end_x: end.0,
+            end_y: end.1,
+            width: 0.4,
+            layer: "F.Fab".to_string(),
+        }
+    }
+
+    #[test]
+    fn pads_inside_body_give_snapped_rect() {
+        let converter = Converter::new(KicadVersion::V6);
+        let mut footprint = footprint();
+        // Rotated 2 x 4 px pad: 4 x 2 px = 1.016 x 0.508 mm
+        footprint.pads.push(pad(0.0, 0.0, 2.0, 4.0, 90.0));
+        footprint.lines.push(fab_line((-5.0, -3.0), (5.0, 3.0)));
+
+        add_courtyard(&mut footprint, CourtyardDensity::Nominal, &converter);
+
+        let rect = &footprint.rects[0];
+        let mm = |px: f64| (converter.px_to_mm(px) * 1e4).round() / 1e4;
+        // Body 2.54 x 1.524 mm plus 0.25 mm, rounded outwards to 0.01 mm
+        assert_eq!(
+            (
+                mm(rect.start_x),
+                mm(rect.start_y),
+                mm(rect.end_x),
+                mm(rect.end_y)
+            ),
+            (-1.52, -1.02, 1.52, 1.02)
+        );
+        assert_eq!(rect.layer, "F.CrtYd");
+        assert!(footprint.polygons.is_empty());
+    }
+
+    #[test]
+    fn pads_outside_body_give_polygon() {
+        let converter = Converter::new(KicadVersion::V6);
+        let mut footprint = footprint();
+        // Pads stick out above and below a wide, flat body
+        footprint.pads.push(pad(0.0, -10.0, 4.0, 4.0, 0.0));
+        footprint.pads.push(pad(0.0, 10.0, 4.0, 4.0, 0.0));
+        footprint.lines.push(fab_line((-20.0, -5.0), (20.0, 5.0)));
+
+        add_courtyard(&mut footprint, CourtyardDensity::Least, &converter);
+
+        assert!(footprint.rects.is_empty());
+        let points = &footprint.polygons[0].points;
+        // A cross: twelve corners
+        assert_eq!(points.len(), 12);
+
+        // Nothing once a courtyard exists
+        add_courtyard(&mut footprint, CourtyardDensity::Most, &converter);
+        assert_eq!(footprint.polygons.len(), 1);
+    }
+}
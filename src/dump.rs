@@ -0,0 +1,89 @@
+use crate::easyeda::{ApiResponse, ComponentData, import_footprint, import_symbol};
+use crate::error::{AppError, Result};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// Write everything nlbn saw for one part under `<dir>/<LCSC_ID>/`:
+/// - `response.json`: the untouched API response
+/// - `data_str.json` / `package_detail.json`: the extracted shape lists
+/// - `symbol.json` / `footprint.json`: the parsed `EeSymbol` / `EeFootprint`
+///
+/// `component_data` is `None` when the response could not be interpreted; the
+/// raw response is still written so the failure can be reproduced.
+pub fn dump_component(
+    dir: &Path,
+    lcsc_id: &str,
+    api_response: &ApiResponse,
+    component_data: Option<&ComponentData>,
+) -> Result<PathBuf> {
+    let part_dir = dir.join(lcsc_id);
+    std::fs::create_dir_all(&part_dir)
+        .map_err(|e| AppError::Other(format!("Failed to create {}: {}", part_dir.display(), e)))?;
+
+    write_json(&part_dir.join("response.json"), api_response)?;
+
+    if let Some(data) = component_data {
+        write_json(&part_dir.join("data_str.json"), &data.data_str)?;
+        write_json(&part_dir.join("package_detail.json"), &data.package_detail)?;
+
+        match import_symbol(&data.data_str) {
+            Ok(symbol) => write_json(&part_dir.join("symbol.json"), &symbol)?,
+            Err(e) => log::warn!("Not dumping symbol for {}: {}", lcsc_id, e),
+        }
+        match import_footprint(&data.package_detail) {
+            Ok(footprint) => write_json(&part_dir.join("footprint.json"), &footprint)?,
+            Err(e) => log::warn!("Not dumping footprint for {}: {}", lcsc_id, e),
+        }
+    }
+
+    log::info!("Dumped raw data for {} to {}", lcsc_id, part_dir.display());
+    Ok(part_dir)
+}
+
+fn write_json<T: Serialize + ?Sized>(path: &Path, value: &T) -> Result<()> {
+    let json = serde_json::to_string_pretty(value)
+        .map_err(|e| AppError::Other(format!("Failed to serialize {}: {}", path.display(), e)))?;
+    std::fs::write(path, json + "\n")
+        .map_err(|e| AppError::Other(format!("Failed to write {}: {}", path.display(), e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::easyeda::EasyedaApi;
+    use crate::easyeda::source::temp_dir;
+
+    #[test]
+    fn dumps_response_shapes_and_parsed_models() {
+        let dir = temp_dir("dump");
+
+        let api_response: ApiResponse = serde_json::from_str(
+            r#"{
+                "success": true,
+                "code": 0,
+                "result": {
+                    "title": "R0603",
+                    "dataStr": {"head": {"x": 0, "y": 0}, "shape": ["R~-10~-5~~~20~10~#880000~1~0~none~gge1~0~"]},
+                    "packageDetail": {"dataStr": {"head": {"x": 0, "y": 0}, "shape": []}}
+                }
+            }"#,
+        )
+        .unwrap();
+        let data = EasyedaApi::parse_component_data("C25804", api_response.clone()).unwrap();
+
+        let part_dir = dump_component(&dir, "C25804", &api_response, Some(&data)).unwrap();
+
+        let response: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(part_dir.join("response.json")).unwrap())
+                .unwrap();
+        assert_eq!(response["code"], 0);
+        let symbol: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(part_dir.join("symbol.json")).unwrap())
+                .unwrap();
+        assert_eq!(symbol["rectangles"][0]["width"], 20.0);
+        assert!(part_dir.join("data_str.json").exists());
+        assert!(part_dir.join("footprint.json").exists());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
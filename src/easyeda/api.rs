@@ -1,51 +1,189 @@
-use crate::easyeda::models::{ApiResponse, ComponentData, Model3dInfo};
-use crate::error::{EasyedaError, Result};
+use crate::easyeda::config::EasyedaApiConfig;
+use crate::easyeda::models::{ApiResponse, ComponentData, Model3dInfo, SearchResult, SymbolPart};
+use crate::easyeda::retry::{self, RateLimiter, RetryPolicy};
+use crate::easyeda::source::temp_path;
+use crate::error::{AppError, EasyedaError, Result};
+use indexmap::IndexMap;
 use reqwest::Client;
 use std::path::Path;
 use tokio::io::AsyncWriteExt;
 
 pub struct EasyedaApi {
     client: Client,
+    config: EasyedaApiConfig,
+    retry: RetryPolicy,
+    /// Shared by every request made through this instance, i.e. all `--parallel` workers
+    limiter: RateLimiter,
 }
 
 impl EasyedaApi {
-    pub fn new() -> Self {
-        Self {
-            client: Client::builder()
-                .user_agent(format!("nlbn/{}", env!("CARGO_PKG_VERSION")))
-                .connect_timeout(std::time::Duration::from_secs(5))
-                .read_timeout(std::time::Duration::from_secs(30))
-                .pool_max_idle_per_host(10)
-                .http2_adaptive_window(true)
-                .build()
-                .expect("Failed to create HTTP client"),
-        }
+    pub fn new() -> Result<Self> {
+        Self::with_config(EasyedaApiConfig::default())
+    }
+
+    pub fn with_config(config: EasyedaApiConfig) -> Result<Self> {
+        Ok(Self {
+            client: config.build_client()?,
+            retry: config.retry_policy(),
+            limiter: config.rate_limiter(),
+            config,
+        })
+    }
+
+    pub fn config(&self) -> &EasyedaApiConfig {
+        &self.config
     }
 
     pub async fn get_component_data(&self, lcsc_id: &str) -> Result<ComponentData> {
-        let url = format!(
-            "https://easyeda.com/api/products/{}/components?version=6.4.19.5",
-            lcsc_id
-        );
+        let api_response = self.fetch_api_response(lcsc_id).await?;
+        Self::parse_component_data(lcsc_id, api_response)
+    }
+
+    /// Fetch the raw component response from EasyEDA without interpreting it
+    pub async fn fetch_api_response(&self, lcsc_id: &str) -> Result<ApiResponse> {
+        let url = self.config.component_url(lcsc_id);
 
         log::info!("Fetching component data for {}", lcsc_id);
 
-        let response = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .map_err(EasyedaError::ApiRequest)?;
+        let what = format!("Component request for {}", lcsc_id);
+        self.request_with_retry(
+            &what,
+            || self.client.get(&url),
+            |response| async move {
+                if !response.status().is_success() {
+                    return Err(EasyedaError::ComponentNotFound(lcsc_id.to_string()).into());
+                }
+                let api_response: ApiResponse = response.json().await.map_err(|e| {
+                    EasyedaError::InvalidData(format!("Failed to parse JSON: {}", e))
+                })?;
+                Ok(api_response)
+            },
+        )
+        .await
+    }
 
-        if !response.status().is_success() {
-            return Err(EasyedaError::ComponentNotFound(lcsc_id.to_string()).into());
+    /// Keyword / MPN search; returns at most `limit` parts that have an LCSC number
+    pub async fn search(&self, query: &str, limit: usize) -> Result<Vec<SearchResult>> {
+        let url = self.config.component_search_url();
+
+        log::info!("Searching EasyEDA for \"{}\"", query);
+
+        let version = self.config.api_version.as_str();
+        let body: serde_json::Value = self
+            .request_with_retry(
+                "Search request",
+                || {
+                    self.client.post(&url).form(&[
+                        ("type", "3"),
+                        ("doctype[]", "2"),
+                        ("returnListStyle", "classifyarr"),
+                        ("wd", query),
+                        ("version", version),
+                    ])
+                },
+                |response| async move {
+                    if !response.status().is_success() {
+                        return Err(EasyedaError::InvalidData(format!(
+                            "Search request failed with status {}",
+                            response.status()
+                        ))
+                        .into());
+                    }
+                    response.json().await.map_err(|e| {
+                        EasyedaError::InvalidData(format!("Failed to parse JSON: {}", e)).into()
+                    })
+                },
+            )
+            .await?;
+
+        if body.get("success").and_then(|v| v.as_bool()) == Some(false) {
+            return Err(EasyedaError::InvalidData(format!(
+                "Search failed: {}",
+                body.get("message")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("unknown error")
+            ))
+            .into());
         }
 
-        let api_response: ApiResponse = response
-            .json()
-            .await
-            .map_err(|e| EasyedaError::InvalidData(format!("Failed to parse JSON: {}", e)))?;
+        let mut results = Self::parse_search_results(&body);
+        results.truncate(limit);
+        Ok(results)
+    }
+
+    /// Extract parts from a search response.
+    ///
+    /// `result.lists` is either an array of parts or an object of arrays keyed by
+    /// library (`lcsc`, `jlc`, ...). Parts without an LCSC number are skipped and
+    /// only the first entry for each LCSC number is kept.
+    pub fn parse_search_results(body: &serde_json::Value) -> Vec<SearchResult> {
+        let lists = body.get("result").and_then(|r| r.get("lists"));
+        let items: Vec<&serde_json::Value> = match lists {
+            Some(serde_json::Value::Array(items)) => items.iter().collect(),
+            Some(serde_json::Value::Object(groups)) => {
+                // LCSC library first; it carries the most complete metadata
+                let mut groups: Vec<_> = groups.iter().collect();
+                groups.sort_by_key(|(name, _)| name.as_str() != "lcsc");
+                groups
+                    .into_iter()
+                    .filter_map(|(_, g)| g.as_array())
+                    .flatten()
+                    .collect()
+            }
+            _ => Vec::new(),
+        };
+
+        let lcsc_re = regex::Regex::new(r"^C\d+$").unwrap();
+        let mut results: Vec<SearchResult> = Vec::new();
+
+        for item in items {
+            let c_para = item
+                .get("dataStr")
+                .and_then(|ds| ds.get("head"))
+                .and_then(|h| h.get("c_para"));
+            let para = |keys: &[&str]| {
+                keys.iter()
+                    .filter_map(|k| c_para.and_then(|cp| cp.get(*k)).and_then(|v| v.as_str()))
+                    .map(|s| s.trim())
+                    .find(|s| !s.is_empty())
+                    .map(|s| s.to_string())
+            };
+
+            let lcsc_id = item
+                .get("lcsc")
+                .and_then(|l| l.get("number"))
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string())
+                .or_else(|| para(&["BOM_Supplier Part", "Supplier Part"]));
+            let Some(lcsc_id) = lcsc_id.filter(|id| lcsc_re.is_match(id)) else {
+                continue;
+            };
+            if results.iter().any(|r| r.lcsc_id == lcsc_id) {
+                continue;
+            }
+
+            let mpn = para(&["BOM_Manufacturer Part", "Manufacturer Part", "name"])
+                .or_else(|| {
+                    item.get("title")
+                        .and_then(|v| v.as_str())
+                        .map(|s| s.to_string())
+                })
+                .unwrap_or_default();
+
+            results.push(SearchResult {
+                lcsc_id,
+                mpn,
+                manufacturer: para(&["BOM_Manufacturer", "Manufacturer"]).unwrap_or_default(),
+                package: para(&["package"]).unwrap_or_default(),
+                has_3d_model: None,
+            });
+        }
+
+        results
+    }
 
+    /// Extract `ComponentData` from a raw EasyEDA component response
+    pub fn parse_component_data(lcsc_id: &str, api_response: ApiResponse) -> Result<ComponentData> {
         if !api_response.success {
             return Err(EasyedaError::ComponentNotFound(lcsc_id.to_string()).into());
         }
@@ -88,25 +226,20 @@ impl EasyedaApi {
 
         log::debug!("Final data_str has {} shapes", data_str.len());
 
+        let sub_parts = Self::extract_sub_parts(result.subparts.as_ref());
+        if !sub_parts.is_empty() {
+            log::debug!("Found {} symbol sub-parts", sub_parts.len());
+        }
+
         let title = result
             .title
             .ok_or_else(|| EasyedaError::InvalidData("Missing title field".to_string()))?;
 
-        let manufacturer = data_str_obj
-            .get("head")
-            .and_then(|h| h.get("c_para"))
-            .and_then(|cp| cp.get("BOM_Manufacturer"))
-            .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .to_string();
+        let c_para = Self::extract_c_para(data_str_obj.get("head").and_then(|h| h.get("c_para")));
+        let c_para_str = |key: &str| c_para.get(key).cloned().unwrap_or_default();
 
-        let package = data_str_obj
-            .get("head")
-            .and_then(|h| h.get("c_para"))
-            .and_then(|cp| cp.get("package"))
-            .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .to_string();
+        let manufacturer = c_para_str("BOM_Manufacturer");
+        let package = c_para_str("package");
 
         let szlcsc_id = result
             .lcsc
@@ -128,13 +261,18 @@ impl EasyedaApi {
                 }
             });
 
-        let jlc_id = data_str_obj
-            .get("head")
-            .and_then(|h| h.get("c_para"))
-            .and_then(|cp| cp.get("BOM_JLCPCB Part Class"))
-            .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .to_string();
+        let jlc_id = c_para_str("BOM_JLCPCB Part Class");
+
+        // `tags` holds the catalog category path, as a list or a single string
+        let category = match result.extra.get("tags") {
+            Some(serde_json::Value::Array(tags)) => tags
+                .iter()
+                .filter_map(|t| t.as_str())
+                .collect::<Vec<_>>()
+                .join(" / "),
+            Some(serde_json::Value::String(tag)) => tag.clone(),
+            _ => String::new(),
+        };
 
         // Build description: use API description if available, otherwise generate from metadata
         let description = match result.description {
@@ -219,33 +357,111 @@ impl EasyedaApi {
             manufacturer,
             datasheet,
             jlc_id,
+            sub_parts,
+            c_para,
+            category,
         })
     }
 
+    /// `c_para` as ordered string pairs; numbers and booleans are stringified, nulls dropped
+    fn extract_c_para(c_para: Option<&serde_json::Value>) -> IndexMap<String, String> {
+        let Some(c_para) = c_para.and_then(|v| v.as_object()) else {
+            return IndexMap::new();
+        };
+
+        c_para
+            .iter()
+            .filter_map(|(key, value)| {
+                let value = match value {
+                    serde_json::Value::String(s) => s.clone(),
+                    serde_json::Value::Number(n) => n.to_string(),
+                    serde_json::Value::Bool(b) => b.to_string(),
+                    _ => return None,
+                };
+                Some((key.clone(), value))
+            })
+            .collect()
+    }
+
+    /// Units of a multi-unit part; each entry is `{"dataStr": {"head": ..., "shape": [...]}}`
+    fn extract_sub_parts(subparts: Option<&serde_json::Value>) -> Vec<SymbolPart> {
+        let Some(subparts) = subparts.and_then(|v| v.as_array()) else {
+            return Vec::new();
+        };
+
+        subparts
+            .iter()
+            .filter_map(|part| {
+                let doc = part.get("dataStr").unwrap_or(part);
+                let head = doc.get("head");
+                let coord = |key: &str| {
+                    head.and_then(|h| h.get(key))
+                        .and_then(|v| v.as_f64())
+                        .unwrap_or(0.0)
+                };
+                let data_str: Vec<String> = doc
+                    .get("shape")?
+                    .as_array()?
+                    .iter()
+                    .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                    .collect();
+
+                Some(SymbolPart {
+                    data_str,
+                    bbox_x: coord("x"),
+                    bbox_y: coord("y"),
+                })
+            })
+            .collect()
+    }
+
     fn extract_3d_model_from_svgnode(shapes: &[String]) -> Option<Model3dInfo> {
         for shape in shapes {
             if shape.starts_with("SVGNODE~") {
                 let parts: Vec<&str> = shape.split('~').collect();
-                if parts.len() > 1 {
-                    if let Ok(svg_data) = serde_json::from_str::<serde_json::Value>(parts[1]) {
-                        if let Some(attrs) = svg_data.get("attrs") {
-                            if let Some(c_etype) = attrs.get("c_etype").and_then(|v| v.as_str()) {
-                                if c_etype == "outline3D" {
-                                    let uuid = attrs
-                                        .get("uuid")
-                                        .and_then(|v| v.as_str())
-                                        .map(|s| s.to_string());
-                                    let title = attrs
-                                        .get("title")
-                                        .and_then(|v| v.as_str())
-                                        .map(|s| s.to_string());
-
-                                    if let (Some(uuid), Some(title)) = (uuid, title) {
-                                        return Some(Model3dInfo { uuid, title });
-                                    }
-                                }
-                            }
-                        }
+                if parts.len() > 1
+                    && let Ok(svg_data) = serde_json::from_str::<serde_json::Value>(parts[1])
+                    && let Some(attrs) = svg_data.get("attrs")
+                    && let Some(c_etype) = attrs.get("c_etype").and_then(|v| v.as_str())
+                    && c_etype == "outline3D"
+                {
+                    let uuid = attrs
+                        .get("uuid")
+                        .and_then(|v| v.as_str())
+                        .map(|s| s.to_string());
+                    let title = attrs
+                        .get("title")
+                        .and_then(|v| v.as_str())
+                        .map(|s| s.to_string());
+
+                    if let (Some(uuid), Some(title)) = (uuid, title) {
+                        let numbers = |key: &str| -> Vec<f64> {
+                            attrs
+                                .get(key)
+                                .and_then(|v| v.as_str())
+                                .unwrap_or("")
+                                .split(',')
+                                .filter_map(|n| n.trim().parse().ok())
+                                .collect()
+                        };
+
+                        let origin = match numbers("c_origin")[..] {
+                            [x, y, ..] => Some((x, y)),
+                            _ => None,
+                        };
+                        let z = numbers("z").first().copied().unwrap_or(0.0);
+                        let rotation = match numbers("c_rotation")[..] {
+                            [x, y, z, ..] => (x, y, z),
+                            _ => (0.0, 0.0, 0.0),
+                        };
+
+                        return Some(Model3dInfo {
+                            uuid,
+                            title,
+                            origin,
+                            z,
+                            rotation,
+                        });
                     }
                 }
             }
@@ -254,16 +470,13 @@ impl EasyedaApi {
     }
 
     pub async fn download_3d_obj(&self, uuid: &str) -> Result<Vec<u8>> {
-        let url = format!("https://modules.easyeda.com/3dmodel/{}", uuid);
-        self.download_with_retry(&url, "OBJ", uuid).await
+        let url = self.config.obj_url(uuid);
+        self.download_bytes(&url, "OBJ", uuid).await
     }
 
     pub async fn download_3d_step(&self, uuid: &str) -> Result<Vec<u8>> {
-        let url = format!(
-            "https://modules.easyeda.com/qAxj6KHrDKw4blvCG8QJPs7Y/{}",
-            uuid
-        );
-        self.download_with_retry(&url, "STEP", uuid).await
+        let url = self.config.step_url(uuid);
+        self.download_bytes(&url, "STEP", uuid).await
     }
 
     /// Stream download directly to a file with atomic write (temp file + rename)
@@ -274,11 +487,8 @@ impl EasyedaApi {
         dest: &Path,
     ) -> Result<()> {
         let url = match model_type {
-            "OBJ" => format!("https://modules.easyeda.com/3dmodel/{}", uuid),
-            "STEP" => format!(
-                "https://modules.easyeda.com/qAxj6KHrDKw4blvCG8QJPs7Y/{}",
-                uuid
-            ),
+            "OBJ" => self.config.obj_url(uuid),
+            "STEP" => self.config.step_url(uuid),
             _ => {
                 return Err(EasyedaError::InvalidData(format!(
                     "Unknown model type: {}",
@@ -288,161 +498,315 @@ impl EasyedaApi {
             }
         };
 
-        const MAX_RETRIES: u32 = 3;
-        let tmp_path = dest.with_extension("tmp");
-
-        for attempt in 1..=MAX_RETRIES {
-            log::info!(
-                "Downloading 3D {} model: {}{}",
-                model_type,
-                uuid,
-                if attempt > 1 {
-                    format!(" (retry {}/{})", attempt, MAX_RETRIES)
-                } else {
-                    String::new()
+        log::info!("Downloading 3D {} model: {}", model_type, uuid);
+
+        let tmp_path = temp_path(dest);
+        let what = format!("{} download for {}", model_type, uuid);
+        self.request_with_retry(
+            &what,
+            || self.client.get(&url),
+            |response| async {
+                if !response.status().is_success() {
+                    return Err(EasyedaError::InvalidData(format!(
+                        "Failed to download {}: {}",
+                        model_type, uuid
+                    ))
+                    .into());
                 }
-            );
-
-            match self.client.get(&url).send().await {
-                Ok(response) => {
-                    if !response.status().is_success() {
-                        if attempt == MAX_RETRIES {
-                            return Err(EasyedaError::InvalidData(format!(
-                                "Failed to download {}: {}",
-                                model_type, uuid
-                            ))
-                            .into());
-                        }
-                        Self::backoff_delay(attempt).await;
-                        continue;
-                    }
+                let result = Self::stream_to_file(response, &tmp_path).await;
+                if result.is_err() {
+                    let _ = tokio::fs::remove_file(&tmp_path).await;
+                }
+                result
+            },
+        )
+        .await?;
 
-                    // Stream response body to temp file with buffered writer
-                    let mut file = tokio::io::BufWriter::with_capacity(
-                        256 * 1024, // 256 KB buffer for binary files
-                        tokio::fs::File::create(&tmp_path).await.map_err(|e| {
-                            EasyedaError::InvalidData(format!("Failed to create temp file: {}", e))
-                        })?,
-                    );
-
-                    let mut stream = response.bytes_stream();
-                    use futures_util::StreamExt;
-                    while let Some(chunk) = stream.next().await {
-                        match chunk {
-                            Ok(bytes) => {
-                                if let Err(e) = file.write_all(&bytes).await {
-                                    let _ = tokio::fs::remove_file(&tmp_path).await;
-                                    if attempt == MAX_RETRIES {
-                                        return Err(EasyedaError::InvalidData(format!(
-                                            "Write error: {}",
-                                            e
-                                        ))
-                                        .into());
-                                    }
-                                    break;
-                                }
-                            }
-                            Err(e) => {
-                                let _ = tokio::fs::remove_file(&tmp_path).await;
-                                if attempt == MAX_RETRIES {
-                                    return Err(EasyedaError::ApiRequest(e).into());
-                                }
-                                log::warn!("Failed to read {} stream, retrying...", model_type);
-                                Self::backoff_delay(attempt).await;
-                                continue;
-                            }
-                        }
-                    }
+        // Atomic rename
+        tokio::fs::rename(&tmp_path, dest)
+            .await
+            .map_err(|e| EasyedaError::InvalidData(format!("Rename error: {}", e)))?;
 
-                    file.flush()
-                        .await
-                        .map_err(|e| EasyedaError::InvalidData(format!("Flush error: {}", e)))?;
-                    drop(file);
+        Ok(())
+    }
 
-                    // Atomic rename
-                    tokio::fs::rename(&tmp_path, dest)
-                        .await
-                        .map_err(|e| EasyedaError::InvalidData(format!("Rename error: {}", e)))?;
+    async fn stream_to_file(response: reqwest::Response, path: &Path) -> Result<()> {
+        // Stream response body to temp file with buffered writer
+        let mut file = tokio::io::BufWriter::with_capacity(
+            256 * 1024, // 256 KB buffer for binary files
+            tokio::fs::File::create(path).await.map_err(|e| {
+                EasyedaError::InvalidData(format!("Failed to create temp file: {}", e))
+            })?,
+        );
 
-                    return Ok(());
-                }
-                Err(e) => {
-                    if attempt == MAX_RETRIES {
-                        return Err(EasyedaError::ApiRequest(e).into());
-                    }
-                    log::warn!("Failed to download {} model, retrying...", model_type);
-                    Self::backoff_delay(attempt).await;
-                }
-            }
+        let mut stream = response.bytes_stream();
+        use futures_util::StreamExt;
+        while let Some(chunk) = stream.next().await {
+            let bytes = chunk.map_err(EasyedaError::ApiRequest)?;
+            file.write_all(&bytes)
+                .await
+                .map_err(|e| EasyedaError::InvalidData(format!("Write error: {}", e)))?;
         }
-        unreachable!()
-    }
 
-    /// Exponential backoff delay: 1s, 2s, 4s, ...
-    async fn backoff_delay(attempt: u32) {
-        let delay = std::time::Duration::from_millis(1000 * 2u64.pow(attempt - 1));
-        tokio::time::sleep(delay).await;
+        file.flush()
+            .await
+            .map_err(|e| EasyedaError::InvalidData(format!("Flush error: {}", e)))?;
+        Ok(())
     }
 
-    async fn download_with_retry(
-        &self,
-        url: &str,
-        model_type: &str,
-        uuid: &str,
-    ) -> Result<Vec<u8>> {
-        const MAX_RETRIES: u32 = 3;
-
-        for attempt in 1..=MAX_RETRIES {
-            log::info!(
-                "Downloading 3D {} model: {}{}",
-                model_type,
-                uuid,
-                if attempt > 1 {
-                    format!(" (retry {}/{})", attempt, MAX_RETRIES)
-                } else {
-                    String::new()
+    async fn download_bytes(&self, url: &str, model_type: &str, uuid: &str) -> Result<Vec<u8>> {
+        log::info!("Downloading 3D {} model: {}", model_type, uuid);
+
+        let what = format!("{} download for {}", model_type, uuid);
+        self.request_with_retry(
+            &what,
+            || self.client.get(url),
+            |response| async move {
+                if !response.status().is_success() {
+                    return Err(EasyedaError::InvalidData(format!(
+                        "Failed to download {}: {}",
+                        model_type, uuid
+                    ))
+                    .into());
                 }
-            );
+                let bytes = response.bytes().await.map_err(EasyedaError::ApiRequest)?;
+                Ok(bytes.to_vec())
+            },
+        )
+        .await
+    }
 
-            match self.client.get(url).send().await {
-                Ok(response) => {
-                    if !response.status().is_success() {
-                        if attempt == MAX_RETRIES {
-                            return Err(EasyedaError::InvalidData(format!(
-                                "Failed to download {}: {}",
-                                model_type, uuid
-                            ))
-                            .into());
-                        }
-                        Self::backoff_delay(attempt).await;
-                        continue;
-                    }
-                    match response.bytes().await {
-                        Ok(bytes) => return Ok(bytes.to_vec()),
-                        Err(e) => {
-                            if attempt == MAX_RETRIES {
-                                return Err(EasyedaError::ApiRequest(e).into());
-                            }
-                            log::warn!("Failed to read {} response body, retrying...", model_type);
-                            Self::backoff_delay(attempt).await;
-                        }
+    /// Send a request under the shared retry policy and rate limiter.
+    ///
+    /// Retryable statuses (408, 429, 5xx) and transport errors are retried,
+    /// including transport errors returned by `read` while it consumes the body.
+    /// Any other response is handed to `read`, whose result is final.
+    async fn request_with_retry<T, B, R, Fut>(&self, what: &str, build: B, read: R) -> Result<T>
+    where
+        B: Fn() -> reqwest::RequestBuilder,
+        R: Fn(reqwest::Response) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let max_attempts = self.retry.max_attempts.max(1);
+        let mut attempt = 1;
+
+        loop {
+            self.limiter.acquire().await;
+
+            let (reason, retry_after, throttled) = match build().send().await {
+                Ok(response) if RetryPolicy::is_retryable(response.status()) => {
+                    let status = response.status();
+                    if attempt >= max_attempts {
+                        return Err(EasyedaError::InvalidData(format!(
+                            "{} failed after {} attempts: HTTP {}",
+                            what, attempt, status
+                        ))
+                        .into());
                     }
+                    (
+                        format!("HTTP {}", status),
+                        retry::retry_after(response.headers()),
+                        status == reqwest::StatusCode::TOO_MANY_REQUESTS,
+                    )
                 }
-                Err(e) => {
-                    if attempt == MAX_RETRIES {
-                        return Err(EasyedaError::ApiRequest(e).into());
+                Ok(response) => match read(response).await {
+                    Err(AppError::Easyeda(EasyedaError::ApiRequest(e)))
+                        if RetryPolicy::is_transient(&e) && attempt < max_attempts =>
+                    {
+                        (e.to_string(), None, false)
                     }
-                    log::warn!("Failed to download {} model, retrying...", model_type);
-                    Self::backoff_delay(attempt).await;
+                    result => return result,
+                },
+                Err(e) if RetryPolicy::is_transient(&e) && attempt < max_attempts => {
+                    (e.to_string(), None, false)
                 }
+                Err(e) => return Err(EasyedaError::ApiRequest(e).into()),
+            };
+
+            let delay = self.retry.delay(attempt, retry_after);
+            log::warn!(
+                "{} failed ({}), retrying in {:.1}s (attempt {}/{})",
+                what,
+                reason,
+                delay.as_secs_f64(),
+                attempt + 1,
+                max_attempts
+            );
+
+            // A 429 slows every worker down; the limiter then does the waiting
+            if throttled && self.limiter.is_enabled() {
+                self.limiter.hold_off(delay).await;
+            } else {
+                tokio::time::sleep(delay).await;
             }
+            attempt += 1;
         }
-        unreachable!()
     }
 }
 
-impl Default for EasyedaApi {
-    fn default() -> Self {
-        Self::new()
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn fetches_component_from_configured_base_url() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/products/C2040/components?version=test")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"success": true, "result": {"title": "RP2040", "dataStr": {"head": {"x": 10, "y": 20, "c_para": {"pre": "U?", "Voltage": 3.3, "BOM_Manufacturer": "Raspberry Pi"}}, "shape": []}}}"#,
+            )
+            .create_async()
+            .await;
+
+        let config = EasyedaApiConfig::new()
+            .api_base_url(server.url())
+            .api_version("test");
+        let api = EasyedaApi::with_config(config).unwrap();
+        let data = api.get_component_data("C2040").await.unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(data.title, "RP2040");
+        assert_eq!(data.bbox_y, 20.0);
+        assert_eq!(data.manufacturer, "Raspberry Pi");
+        let c_para: Vec<_> = data.c_para.iter().collect();
+        assert_eq!(c_para[0], (&"pre".to_string(), &"U?".to_string()));
+        assert_eq!(c_para[1], (&"Voltage".to_string(), &"3.3".to_string()));
+    }
+
+    #[test]
+    fn parses_symbol_sub_parts() {
+        let response: ApiResponse = serde_json::from_str(
+            r#"{"success": true, "result": {
+                "title": "LM358",
+                "dataStr": {"head": {"x": 0, "y": 0}, "shape": []},
+                "subparts": [
+                    {"dataStr": {"head": {"x": 400, "y": 300}, "shape": ["P~show~0~1~380~300~180~gge1~0"]}},
+                    {"dataStr": {"head": {"x": 500, "y": 350}, "shape": ["P~show~0~7~480~350~180~gge2~0"]}}
+                ]
+            }}"#,
+        )
+        .unwrap();
+
+        let data = EasyedaApi::parse_component_data("C7950", response).unwrap();
+        assert_eq!(data.sub_parts.len(), 2);
+        assert_eq!(
+            (data.sub_parts[1].bbox_x, data.sub_parts[1].bbox_y),
+            (500.0, 350.0)
+        );
+        assert_eq!(data.sub_parts[0].data_str.len(), 1);
+    }
+
+    #[test]
+    fn parses_outline3d_placement() {
+        let shapes = vec![
+            r#"SVGNODE~{"gId":"g1","nodeName":"g","attrs":{"c_etype":"outline3D","uuid":"8d1d","title":"SOT-23-3_L2.9-W1.3","c_origin":"4010.5,2995","z":"-1.2","c_rotation":"0,0,90"}}"#
+                .to_string(),
+        ];
+
+        let model = EasyedaApi::extract_3d_model_from_svgnode(&shapes).unwrap();
+        assert_eq!(model.uuid, "8d1d");
+        assert_eq!(model.origin, Some((4010.5, 2995.0)));
+        assert_eq!(model.z, -1.2);
+        assert_eq!(model.rotation, (0.0, 0.0, 90.0));
+    }
+
+    #[tokio::test]
+    async fn retries_server_errors_and_honors_retry_after() {
+        let mut server = mockito::Server::new_async().await;
+        let path = "/api/products/C2040/components?version=test";
+        let unavailable = server
+            .mock("GET", path)
+            .with_status(503)
+            .with_header("retry-after", "0")
+            .expect(2)
+            .create_async()
+            .await;
+        let ok = server
+            .mock("GET", path)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"success": true, "result": {"title": "RP2040", "dataStr": {"head": {"x": 0, "y": 0}, "shape": []}}}"#,
+            )
+            .create_async()
+            .await;
+
+        let config = EasyedaApiConfig {
+            retry_base_delay_ms: 1,
+            ..EasyedaApiConfig::new()
+                .api_base_url(server.url())
+                .api_version("test")
+        };
+        let api = EasyedaApi::with_config(config.clone()).unwrap();
+        let data = api.get_component_data("C2040").await.unwrap();
+
+        unavailable.assert_async().await;
+        ok.assert_async().await;
+        assert_eq!(data.title, "RP2040");
+
+        // Client errors are final
+        let not_found = server
+            .mock("GET", "/api/products/C1/components?version=test")
+            .with_status(404)
+            .expect(1)
+            .create_async()
+            .await;
+        assert!(api.get_component_data("C1").await.is_err());
+        not_found.assert_async().await;
+
+        // Out of attempts: the last status is reported instead of "not found"
+        server
+            .mock("GET", "/api/products/C2/components?version=test")
+            .with_status(500)
+            .create_async()
+            .await;
+        let api = EasyedaApi::with_config(config.max_attempts(1)).unwrap();
+        let err = api.get_component_data("C2").await.unwrap_err().to_string();
+        assert!(err.contains("HTTP 500"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn searches_configured_endpoint() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/search")
+            .match_body(mockito::Matcher::UrlEncoded(
+                "wd".to_string(),
+                "STM32F103C8T6".to_string(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"success": true, "result": {"lists": {
+                    "lcsc": [
+                        {"title": "STM32F103C8T6", "lcsc": {"number": "C8734"},
+                         "dataStr": {"head": {"c_para": {"BOM_Manufacturer": "ST", "package": "LQFP-48"}}}},
+                        {"title": "No LCSC number", "dataStr": {"head": {"c_para": {}}}}
+                    ],
+                    "jlc": [
+                        {"title": "STM32F103C8T6", "lcsc": {"number": "C8734"}},
+                        {"title": "STM32F103C8T7", "dataStr": {"head": {"c_para": {"BOM_Supplier Part": "C77994", "BOM_Manufacturer Part": "STM32F103C8T7"}}}}
+                    ]
+                }}}"#,
+            )
+            .create_async()
+            .await;
+
+        let config = EasyedaApiConfig::new().search_url(format!("{}/search", server.url()));
+        let api = EasyedaApi::with_config(config).unwrap();
+        let results = api.search("STM32F103C8T6", 10).await.unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].lcsc_id, "C8734");
+        assert_eq!(results[0].manufacturer, "ST");
+        assert_eq!(results[0].package, "LQFP-48");
+        assert_eq!(results[1].mpn, "STM32F103C8T7");
+        assert_eq!(results[1].has_3d_model, None);
+
+        assert_eq!(api.search("STM32F103C8T6", 1).await.unwrap().len(), 1);
     }
 }
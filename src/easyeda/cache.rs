@@ -0,0 +1,334 @@
+use crate::easyeda::models::ApiResponse;
+use crate::easyeda::source::{ComponentSource, temp_path};
+use crate::error::{EasyedaError, Result};
+use async_trait::async_trait;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// On-disk cache of raw EasyEDA responses and 3D model payloads.
+///
+/// Layout under the cache root:
+/// - `components/<LCSC_ID>.json`: untouched API response, expires after `ttl`
+/// - `models/<uuid>.obj` / `models/<uuid>.step`: model payloads, never expire
+///   since a model UUID always refers to the same file
+pub struct CachedSource {
+    root: PathBuf,
+    ttl: Duration,
+    refresh: bool,
+    upstream: Option<Box<dyn ComponentSource>>,
+}
+
+impl CachedSource {
+    /// `upstream` is `None` in offline mode: misses fail and stale entries are still served
+    pub fn new(
+        root: &Path,
+        ttl: Duration,
+        refresh: bool,
+        upstream: Option<Box<dyn ComponentSource>>,
+    ) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            ttl,
+            refresh,
+            upstream,
+        }
+    }
+
+    pub fn component_path(&self, lcsc_id: &str) -> PathBuf {
+        self.root
+            .join("components")
+            .join(format!("{}.json", lcsc_id))
+    }
+
+    pub fn model_path(&self, uuid: &str, model_type: &str) -> Result<PathBuf> {
+        let extension = match model_type {
+            "OBJ" => "obj",
+            "STEP" => "step",
+            _ => {
+                return Err(EasyedaError::InvalidData(format!(
+                    "Unknown model type: {}",
+                    model_type
+                ))
+                .into());
+            }
+        };
+        Ok(self
+            .root
+            .join("models")
+            .join(format!("{}.{}", uuid, extension)))
+    }
+
+    fn is_fresh(&self, path: &Path) -> bool {
+        std::fs::metadata(path)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .map(|age| age < self.ttl)
+            .unwrap_or(false)
+    }
+
+    fn upstream(&self, what: &str) -> Result<&dyn ComponentSource> {
+        self.upstream.as_deref().ok_or_else(|| {
+            EasyedaError::InvalidData(format!("{} is not cached and --offline is set", what)).into()
+        })
+    }
+
+    /// Write to a temp file next to `path`, then rename into place
+    async fn store(path: &Path, data: &[u8]) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await.map_err(|e| {
+                EasyedaError::InvalidData(format!("Failed to create cache directory: {}", e))
+            })?;
+        }
+        let tmp_path = temp_path(path);
+        let result = match tokio::fs::write(&tmp_path, data).await {
+            Ok(()) => tokio::fs::rename(&tmp_path, path).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            let _ = tokio::fs::remove_file(&tmp_path).await;
+            return Err(EasyedaError::InvalidData(format!("Failed to write cache: {}", e)).into());
+        }
+        Ok(())
+    }
+
+    async fn load_component(path: &Path) -> Result<ApiResponse> {
+        let content = tokio::fs::read_to_string(path)
+            .await
+            .map_err(|e| EasyedaError::InvalidData(format!("Failed to read cache: {}", e)))?;
+        Ok(serde_json::from_str(&content).map_err(EasyedaError::JsonParse)?)
+    }
+}
+
+#[async_trait]
+impl ComponentSource for CachedSource {
+    async fn fetch_api_response(&self, lcsc_id: &str) -> Result<ApiResponse> {
+        let path = self.component_path(lcsc_id);
+        let cached = path.exists();
+
+        if cached && !self.refresh && (self.upstream.is_none() || self.is_fresh(&path)) {
+            log::info!("Using cached component data for {}", lcsc_id);
+            return Self::load_component(&path).await;
+        }
+
+        let api_response = match self.upstream.as_deref() {
+            Some(upstream) => upstream.fetch_api_response(lcsc_id).await?,
+            None => return Err(EasyedaError::ComponentNotFound(lcsc_id.to_string()).into()),
+        };
+
+        // Only successful lookups are worth keeping
+        if api_response.success {
+            let data = serde_json::to_vec(&api_response).map_err(EasyedaError::JsonParse)?;
+            if let Err(e) = Self::store(&path, &data).await {
+                log::warn!("Failed to cache component data for {}: {}", lcsc_id, e);
+            }
+        }
+
+        Ok(api_response)
+    }
+
+    async fn download_3d_obj(&self, uuid: &str) -> Result<Vec<u8>> {
+        let path = self.model_path(uuid, "OBJ")?;
+
+        if path.exists() && !self.refresh {
+            log::info!("Using cached 3D OBJ model: {}", uuid);
+            return tokio::fs::read(&path).await.map_err(|e| {
+                EasyedaError::InvalidData(format!("Failed to read cache: {}", e)).into()
+            });
+        }
+
+        let data = self
+            .upstream(&format!("OBJ model {}", uuid))?
+            .download_3d_obj(uuid)
+            .await?;
+        if let Err(e) = Self::store(&path, &data).await {
+            log::warn!("Failed to cache OBJ model {}: {}", uuid, e);
+        }
+        Ok(data)
+    }
+
+    async fn download_3d_to_file(&self, uuid: &str, model_type: &str, dest: &Path) -> Result<()> {
+        let path = self.model_path(uuid, model_type)?;
+
+        if !path.exists() || self.refresh {
+            let upstream = self.upstream(&format!("{} model {}", model_type, uuid))?;
+            if let Some(parent) = path.parent() {
+                tokio::fs::create_dir_all(parent).await.map_err(|e| {
+                    EasyedaError::InvalidData(format!("Failed to create cache directory: {}", e))
+                })?;
+            }
+            // Download next to the cache entry and rename it into place, so an
+            // interrupted or failed download never leaves a partial model behind
+            let tmp_path = temp_path(&path);
+            if let Err(e) = upstream
+                .download_3d_to_file(uuid, model_type, &tmp_path)
+                .await
+            {
+                let _ = tokio::fs::remove_file(&tmp_path).await;
+                return Err(e);
+            }
+            if let Err(e) = tokio::fs::rename(&tmp_path, &path).await {
+                let _ = tokio::fs::remove_file(&tmp_path).await;
+                return Err(
+                    EasyedaError::InvalidData(format!("Failed to write cache: {}", e)).into(),
+                );
+            }
+        } else {
+            log::info!("Using cached 3D {} model: {}", model_type, uuid);
+        }
+
+        tokio::fs::copy(&path, dest).await.map_err(|e| {
+            EasyedaError::InvalidData(format!("Failed to copy cached model: {}", e))
+        })?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::easyeda::source::{LocalSource, temp_dir};
+
+    const RESPONSE: &str = r#"{"success": true, "result": {"title": "NE555DR", "dataStr": {"head": {"x": 0, "y": 0}, "shape": []}}}"#;
+
+    #[tokio::test]
+    async fn serves_cached_data_without_upstream() {
+        let upstream_dir = temp_dir("cache-upstream");
+        let cache_dir = temp_dir("cache-root");
+        std::fs::write(upstream_dir.join("C7593.json"), RESPONSE).unwrap();
+        std::fs::write(upstream_dir.join("abc.obj"), b"v 0 0 0").unwrap();
+        std::fs::write(upstream_dir.join("abc.step"), b"STEP").unwrap();
+
+        let online = CachedSource::new(
+            &cache_dir,
+            Duration::from_secs(3600),
+            false,
+            Some(Box::new(LocalSource::new(&upstream_dir))),
+        );
+        online.get_component_data("C7593").await.unwrap();
+        online.download_3d_obj("abc").await.unwrap();
+        online
+            .download_3d_to_file("abc", "STEP", &cache_dir.join("first.step"))
+            .await
+            .unwrap();
+        assert!(cache_dir.join("components").join("C7593.json").exists());
+
+        let offline = CachedSource::new(&cache_dir, Duration::ZERO, false, None);
+        let data = offline.get_component_data("C7593").await.unwrap();
+        assert_eq!(data.title, "NE555DR");
+        assert_eq!(offline.download_3d_obj("abc").await.unwrap(), b"v 0 0 0");
+        let dest = cache_dir.join("copy.step");
+        offline
+            .download_3d_to_file("abc", "STEP", &dest)
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read(&dest).unwrap(), b"STEP");
+        assert!(offline.get_component_data("C1").await.is_err());
+
+        let _ = std::fs::remove_dir_all(upstream_dir);
+        let _ = std::fs::remove_dir_all(cache_dir);
+    }
+
+    #[tokio::test]
+    async fn refetches_expired_entries() {
+        let upstream_dir = temp_dir("cache-ttl-upstream");
+        let cache_dir = temp_dir("cache-ttl-root");
+        std::fs::write(upstream_dir.join("C7593.json"), RESPONSE).unwrap();
+
+        let source = CachedSource::new(
+            &cache_dir,
+            Duration::ZERO,
+            false,
+            Some(Box::new(LocalSource::new(&upstream_dir))),
+        );
+        source.get_component_data("C7593").await.unwrap();
+
+        // Expired entry with a missing upstream record fails instead of serving stale data
+        std::fs::remove_file(upstream_dir.join("C7593.json")).unwrap();
+        assert!(source.get_component_data("C7593").await.is_err());
+
+        let _ = std::fs::remove_dir_all(upstream_dir);
+        let _ = std::fs::remove_dir_all(cache_dir);
+    }
+
+    #[tokio::test]
+    async fn failed_model_download_leaves_no_cache_entry() {
+        let upstream_dir = temp_dir("cache-partial-upstream");
+        let cache_dir = temp_dir("cache-partial-root");
+
+        let source = CachedSource::new(
+            &cache_dir,
+            Duration::from_secs(3600),
+            false,
+            Some(Box::new(LocalSource::new(&upstream_dir))),
+        );
+        let dest = cache_dir.join("missing.step");
+        assert!(
+            source
+                .download_3d_to_file("missing", "STEP", &dest)
+                .await
+                .is_err()
+        );
+
+        let models = cache_dir.join("models");
+        assert!(std::fs::read_dir(&models).unwrap().next().is_none());
+
+        let _ = std::fs::remove_dir_all(upstream_dir);
+        let _ = std::fs::remove_dir_all(cache_dir);
+    }
+
+    #[tokio::test]
+    async fn concurrent_writers_do_not_share_temp_files() {
+        let upstream_dir = temp_dir("cache-concurrent-upstream");
+        let cache_dir = temp_dir("cache-concurrent-root");
+        std::fs::write(upstream_dir.join("abc.obj"), b"v 0 0 0").unwrap();
+        std::fs::write(upstream_dir.join("abc.step"), b"STEP").unwrap();
+
+        let path = cache_dir.join("components").join("C7593.json");
+        let payloads: Vec<Vec<u8>> = (0..16).map(|i| vec![b'a' + i; 4096]).collect();
+        let stores = payloads.iter().map(|data| CachedSource::store(&path, data));
+        for result in futures_util::future::join_all(stores).await {
+            result.unwrap();
+        }
+        assert!(payloads.contains(&std::fs::read(&path).unwrap()));
+
+        // Components sharing a model, plus the OBJ and STEP of one uuid
+        let source = CachedSource::new(
+            &cache_dir,
+            Duration::from_secs(3600),
+            true,
+            Some(Box::new(LocalSource::new(&upstream_dir))),
+        );
+        let downloads = (0..8).map(|i| {
+            let model_type = if i % 2 == 0 { "OBJ" } else { "STEP" };
+            let dest = cache_dir.join(format!("out{}.{}", i, model_type));
+            let source = &source;
+            async move {
+                source
+                    .download_3d_to_file("abc", model_type, &dest)
+                    .await
+                    .unwrap();
+                std::fs::read(&dest).unwrap()
+            }
+        });
+        for (i, data) in futures_util::future::join_all(downloads)
+            .await
+            .into_iter()
+            .enumerate()
+        {
+            let expected: &[u8] = if i % 2 == 0 { b"v 0 0 0" } else { b"STEP" };
+            assert_eq!(data, expected);
+        }
+
+        let leftovers: Vec<_> = std::fs::read_dir(cache_dir.join("models"))
+            .unwrap()
+            .chain(std::fs::read_dir(cache_dir.join("components")).unwrap())
+            .map(|entry| entry.unwrap().file_name())
+            .filter(|name| name.to_string_lossy().ends_with(".part"))
+            .collect();
+        assert!(leftovers.is_empty(), "{:?}", leftovers);
+
+        let _ = std::fs::remove_dir_all(upstream_dir);
+        let _ = std::fs::remove_dir_all(cache_dir);
+    }
+}
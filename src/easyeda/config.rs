@@ -0,0 +1,265 @@
+use crate::easyeda::retry::{RateLimiter, RetryPolicy};
+use crate::error::{EasyedaError, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub const DEFAULT_API_BASE_URL: &str = "https://easyeda.com";
+pub const DEFAULT_MODEL_BASE_URL: &str = "https://modules.easyeda.com";
+pub const DEFAULT_API_VERSION: &str = "6.4.19.5";
+
+/// Endpoints and HTTP client settings for `EasyedaApi`.
+///
+/// Can be built in code with the chained setters or loaded from a TOML file:
+///
+/// ```toml
+/// api_base_url = "https://easyeda.mirror.example.com"
+/// connect_timeout_secs = 10
+/// proxy = "http://proxy.example.com:3128"
+/// ca_certs = ["/etc/ssl/corp-root.pem"]
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EasyedaApiConfig {
+    /// Serves `/api/products/{id}/components`
+    pub api_base_url: String,
+    /// Serves the OBJ (`/3dmodel/{uuid}`) and STEP model downloads
+    pub model_base_url: String,
+    /// Keyword search endpoint; defaults to `{api_base_url}/api/components/search`
+    pub search_url: Option<String>,
+    pub api_version: String,
+    pub connect_timeout_secs: u64,
+    pub read_timeout_secs: u64,
+    pub proxy: Option<String>,
+    /// Extra PEM files trusted in addition to the built-in roots
+    pub ca_certs: Vec<PathBuf>,
+    pub user_agent: String,
+    /// Attempts per request, including the first, for 408/429/5xx and transport errors
+    pub max_attempts: u32,
+    pub retry_base_delay_ms: u64,
+    pub retry_max_delay_secs: u64,
+    /// Requests per second across all workers; `0` disables the limiter
+    pub rate_limit: f64,
+    /// Requests that may be sent back to back before the rate limit applies
+    pub rate_limit_burst: u32,
+}
+
+impl Default for EasyedaApiConfig {
+    fn default() -> Self {
+        Self {
+            api_base_url: DEFAULT_API_BASE_URL.to_string(),
+            model_base_url: DEFAULT_MODEL_BASE_URL.to_string(),
+            search_url: None,
+            api_version: DEFAULT_API_VERSION.to_string(),
+            connect_timeout_secs: 5,
+            read_timeout_secs: 30,
+            proxy: None,
+            ca_certs: Vec::new(),
+            user_agent: format!("nlbn/{}", env!("CARGO_PKG_VERSION")),
+            max_attempts: 3,
+            retry_base_delay_ms: 1000,
+            retry_max_delay_secs: 30,
+            rate_limit: 5.0,
+            rate_limit_burst: 10,
+        }
+    }
+}
+
+impl EasyedaApiConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load a TOML config file; missing keys keep their defaults
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            EasyedaError::Config(format!("Failed to read {}: {}", path.display(), e))
+        })?;
+        toml::from_str(&content).map_err(|e| {
+            EasyedaError::Config(format!("Failed to parse {}: {}", path.display(), e)).into()
+        })
+    }
+
+    pub fn api_base_url(mut self, url: impl Into<String>) -> Self {
+        self.api_base_url = url.into();
+        self
+    }
+
+    pub fn model_base_url(mut self, url: impl Into<String>) -> Self {
+        self.model_base_url = url.into();
+        self
+    }
+
+    pub fn search_url(mut self, url: impl Into<String>) -> Self {
+        self.search_url = Some(url.into());
+        self
+    }
+
+    pub fn api_version(mut self, version: impl Into<String>) -> Self {
+        self.api_version = version.into();
+        self
+    }
+
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout_secs = timeout.as_secs();
+        self
+    }
+
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout_secs = timeout.as_secs();
+        self
+    }
+
+    pub fn proxy(mut self, proxy: impl Into<String>) -> Self {
+        self.proxy = Some(proxy.into());
+        self
+    }
+
+    pub fn ca_cert(mut self, path: impl Into<PathBuf>) -> Self {
+        self.ca_certs.push(path.into());
+        self
+    }
+
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    pub fn max_attempts(mut self, attempts: u32) -> Self {
+        self.max_attempts = attempts;
+        self
+    }
+
+    pub fn rate_limit(mut self, requests_per_second: f64) -> Self {
+        self.rate_limit = requests_per_second;
+        self
+    }
+
+    pub fn retry_policy(&self) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: self.max_attempts.max(1),
+            base_delay: Duration::from_millis(self.retry_base_delay_ms),
+            max_delay: Duration::from_secs(self.retry_max_delay_secs),
+        }
+    }
+
+    pub fn rate_limiter(&self) -> RateLimiter {
+        RateLimiter::new(self.rate_limit, self.rate_limit_burst)
+    }
+
+    pub fn component_url(&self, lcsc_id: &str) -> String {
+        format!(
+            "{}/api/products/{}/components?version={}",
+            self.api_base_url.trim_end_matches('/'),
+            lcsc_id,
+            self.api_version
+        )
+    }
+
+    pub fn component_search_url(&self) -> String {
+        match &self.search_url {
+            Some(url) => url.clone(),
+            None => format!(
+                "{}/api/components/search",
+                self.api_base_url.trim_end_matches('/')
+            ),
+        }
+    }
+
+    pub fn obj_url(&self, uuid: &str) -> String {
+        format!(
+            "{}/3dmodel/{}",
+            self.model_base_url.trim_end_matches('/'),
+            uuid
+        )
+    }
+
+    pub fn step_url(&self, uuid: &str) -> String {
+        format!(
+            "{}/qAxj6KHrDKw4blvCG8QJPs7Y/{}",
+            self.model_base_url.trim_end_matches('/'),
+            uuid
+        )
+    }
+
+    /// Build the HTTP client described by this config
+    pub fn build_client(&self) -> Result<reqwest::Client> {
+        let mut builder = reqwest::Client::builder()
+            .user_agent(&self.user_agent)
+            .connect_timeout(Duration::from_secs(self.connect_timeout_secs))
+            .read_timeout(Duration::from_secs(self.read_timeout_secs))
+            .pool_max_idle_per_host(10)
+            .http2_adaptive_window(true);
+
+        if let Some(proxy) = &self.proxy {
+            let proxy = reqwest::Proxy::all(proxy)
+                .map_err(|e| EasyedaError::Config(format!("Invalid proxy {}: {}", proxy, e)))?;
+            builder = builder.proxy(proxy);
+        }
+
+        for path in &self.ca_certs {
+            let pem = std::fs::read(path).map_err(|e| {
+                EasyedaError::Config(format!("Failed to read {}: {}", path.display(), e))
+            })?;
+            let certs = reqwest::Certificate::from_pem_bundle(&pem).map_err(|e| {
+                EasyedaError::Config(format!("Invalid certificate {}: {}", path.display(), e))
+            })?;
+            builder = builder.tls_certs_merge(certs);
+        }
+
+        Ok(builder.build().map_err(EasyedaError::ApiRequest)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_urls_from_base_urls() {
+        let config = EasyedaApiConfig::new()
+            .api_base_url("http://127.0.0.1:8080/")
+            .model_base_url("http://127.0.0.1:8081")
+            .api_version("1.0");
+
+        assert_eq!(
+            config.component_url("C2040"),
+            "http://127.0.0.1:8080/api/products/C2040/components?version=1.0"
+        );
+        assert_eq!(
+            config.component_search_url(),
+            "http://127.0.0.1:8080/api/components/search"
+        );
+        assert_eq!(config.obj_url("abc"), "http://127.0.0.1:8081/3dmodel/abc");
+        assert_eq!(
+            config.step_url("abc"),
+            "http://127.0.0.1:8081/qAxj6KHrDKw4blvCG8QJPs7Y/abc"
+        );
+    }
+
+    #[test]
+    fn parses_partial_toml_config() {
+        let config: EasyedaApiConfig = toml::from_str(
+            r#"
+            api_base_url = "https://mirror.example.com"
+            read_timeout_secs = 60
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.api_base_url, "https://mirror.example.com");
+        assert_eq!(config.read_timeout_secs, 60);
+        assert_eq!(config.model_base_url, DEFAULT_MODEL_BASE_URL);
+        assert_eq!(config.api_version, DEFAULT_API_VERSION);
+    }
+
+    #[test]
+    fn rejects_invalid_proxy() {
+        let err = EasyedaApiConfig::new()
+            .proxy("not a url")
+            .build_client()
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Invalid proxy"));
+    }
+}
@@ -0,0 +1,233 @@
+use crate::converter::sanitize_name;
+use crate::easyeda::api::EasyedaApi;
+use crate::easyeda::models::{ApiResponse, ComponentData};
+use crate::error::{EasyedaError, Result};
+use serde_json::{Value, json};
+use std::path::Path;
+
+/// EasyEDA Std `head.docType` of a library symbol
+const DOC_TYPE_SYMBOL: &str = "2";
+/// EasyEDA Std `head.docType` of a library footprint
+const DOC_TYPE_FOOTPRINT: &str = "4";
+
+/// Load a component from a JSON file instead of the API.
+///
+/// Accepts an EasyEDA Std symbol or footprint source document exported from the
+/// editor (`{"head": ..., "shape": [...]}`), a component object with `dataStr`
+/// and `packageDetail`, or a saved API response. Parts without an LCSC
+/// `Supplier Part` get an empty `lcsc_id`; see [`exported_part_id`].
+pub fn load_exported_component(path: &Path) -> Result<ComponentData> {
+    let content = std::fs::read_to_string(path).map_err(|e| {
+        EasyedaError::InvalidData(format!("Failed to read {}: {}", path.display(), e))
+    })?;
+    let value: Value = serde_json::from_str(&content).map_err(EasyedaError::JsonParse)?;
+
+    let mut result = if value.get("success").is_some() && value.get("result").is_some() {
+        value["result"].clone()
+    } else if value.get("dataStr").is_some() || value.get("packageDetail").is_some() {
+        value
+    } else if value.get("head").is_some() {
+        wrap_document(value)?
+    } else {
+        return Err(EasyedaError::InvalidData(format!(
+            "{} is not an EasyEDA source document",
+            path.display()
+        ))
+        .into());
+    };
+
+    if let Some(data_str) = result.get_mut("dataStr") {
+        normalize_document(data_str);
+    }
+    if let Some(package) = result
+        .get_mut("packageDetail")
+        .and_then(|p| p.get_mut("dataStr"))
+    {
+        normalize_document(package);
+    }
+    if result.get("dataStr").is_none() {
+        result["dataStr"] = json!({"head": {}, "shape": []});
+    }
+
+    let lcsc_id = ["dataStr", "packageDetail"]
+        .iter()
+        .filter_map(|key| {
+            let doc = result.get(*key)?;
+            doc.get("head").or_else(|| doc.get("dataStr")?.get("head"))
+        })
+        .filter_map(|head| {
+            let c_para = head.get("c_para")?;
+            ["Supplier Part", "BOM_Supplier Part"]
+                .iter()
+                .find_map(|k| c_para.get(*k)?.as_str())
+        })
+        .map(|s| s.trim().to_string())
+        .find(|s| s.len() > 1 && s.starts_with('C') && s[1..].chars().all(|c| c.is_ascii_digit()))
+        .unwrap_or_default();
+
+    if result.get("title").and_then(|t| t.as_str()).is_none() {
+        let c_para = |key: &str, para: &str| {
+            result
+                .get(key)
+                .and_then(|d| d.get("head").or_else(|| d.get("dataStr")?.get("head")))
+                .and_then(|h| h.get("c_para"))
+                .and_then(|c| c.get(para))
+                .and_then(|v| v.as_str())
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_string())
+        };
+        let title = c_para("dataStr", "name")
+            .or_else(|| c_para("packageDetail", "package"))
+            .unwrap_or_else(|| file_stem(path).to_string());
+        result["title"] = Value::String(title);
+    }
+
+    let api_response: ApiResponse = serde_json::from_value(json!({
+        "success": true,
+        "result": result,
+    }))
+    .map_err(EasyedaError::JsonParse)?;
+    let data = EasyedaApi::parse_component_data(&lcsc_id, api_response)?;
+    log::info!("Loaded {} from {}", data.title, path.display());
+    Ok(data)
+}
+
+/// Suffix for the library names of a component loaded from `path`: its LCSC
+/// number, or the sanitized file name when the document has none
+pub fn exported_part_id(path: &Path, data: &ComponentData) -> String {
+    if data.lcsc_id.is_empty() {
+        sanitize_name(file_stem(path))
+    } else {
+        data.lcsc_id.clone()
+    }
+}
+
+fn file_stem(path: &Path) -> &str {
+    path.file_stem().and_then(|s| s.to_str()).unwrap_or("part")
+}
+
+/// Place a single symbol or footprint document where the API would put it
+fn wrap_document(doc: Value) -> Result<Value> {
+    let doc_type = match doc["head"].get("docType") {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Number(n)) => n.to_string(),
+        _ => String::new(),
+    };
+
+    let is_footprint = match doc_type.as_str() {
+        DOC_TYPE_SYMBOL => false,
+        DOC_TYPE_FOOTPRINT => true,
+        "" => shapes_of(&doc).iter().any(|s| s.starts_with("PAD~")),
+        other => {
+            return Err(EasyedaError::InvalidData(format!(
+                "Unsupported EasyEDA document type {} (expected a library symbol or footprint)",
+                other
+            ))
+            .into());
+        }
+    };
+
+    Ok(if is_footprint {
+        json!({"packageDetail": {"dataStr": doc}})
+    } else {
+        json!({"dataStr": doc})
+    })
+}
+
+fn shapes_of(doc: &Value) -> Vec<String> {
+    match doc.get("shape") {
+        Some(Value::Array(shapes)) => shapes
+            .iter()
+            .filter_map(|s| s.as_str().map(|s| s.to_string()))
+            .collect(),
+        // Older exports join all shapes into one string
+        Some(Value::String(shapes)) => shapes
+            .split("#@$")
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_string())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Editor exports store the origin as strings and may join shapes into one string
+fn normalize_document(doc: &mut Value) {
+    if doc.get("shape").is_some_and(|s| s.is_string()) {
+        doc["shape"] = json!(shapes_of(doc));
+    }
+    if let Some(head) = doc.get_mut("head").and_then(|h| h.as_object_mut()) {
+        for key in ["x", "y"] {
+            if let Some(n) = head
+                .get(key)
+                .and_then(|v| v.as_str())
+                .and_then(|s| s.trim().parse::<f64>().ok())
+            {
+                head.insert(key.to_string(), json!(n));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::easyeda::source::temp_dir;
+    use std::path::PathBuf;
+
+    fn write_temp(name: &str, content: &str) -> PathBuf {
+        let path = temp_dir("export").join(name);
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn loads_exported_symbol_document() {
+        let path = write_temp(
+            "My Sensor.json",
+            r#"{
+                "head": {"docType": "2", "x": "400", "y": "300", "c_para": {"pre": "U?", "name": "MySensor"}},
+                "canvas": "CA~1000~1000~#FFFFFF~yes~#CCCCCC~5~1000~1000~line~5~pixel~5~400~300",
+                "shape": "R~390~290~2~2~20~20~#880000~1~0~none~gge1~0~#@$T~L~400~300~0~#000080~Arial~~~~~comment~U1~1~start~gge2~0~"
+            }"#,
+        );
+
+        let data = load_exported_component(&path).unwrap();
+        assert_eq!(data.lcsc_id, "");
+        assert_eq!(exported_part_id(&path, &data), "My_Sensor");
+        assert_eq!(data.title, "MySensor");
+        assert_eq!((data.bbox_x, data.bbox_y), (400.0, 300.0));
+        assert_eq!(data.data_str.len(), 2);
+        assert!(data.package_detail.is_empty());
+
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn loads_exported_footprint_document() {
+        let path = write_temp(
+            "custom.json",
+            r#"{
+                "head": {"docType": "4", "x": 4000, "y": 3000, "c_para": {"package": "SOT-23-ALT", "Supplier Part": "C20917"}},
+                "shape": ["PAD~RECT~3990~2990~2~2~1~~1~0~3989 2989 3991 2989 3991 2991 3989 2991~0~gge1~0~~Y~0~~~3990,2990"]
+            }"#,
+        );
+
+        let data = load_exported_component(&path).unwrap();
+        assert_eq!(data.lcsc_id, "C20917");
+        assert_eq!(exported_part_id(&path, &data), "C20917");
+        assert_eq!(data.title, "SOT-23-ALT");
+        assert_eq!(data.package_bbox_x, 4000.0);
+        assert_eq!(data.package_detail.len(), 1);
+        assert!(data.data_str.is_empty());
+
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn rejects_schematic_documents() {
+        let path = write_temp("sheet.json", r#"{"head": {"docType": "1"}, "shape": []}"#);
+        let err = load_exported_component(&path).unwrap_err().to_string();
+        assert!(err.contains("Unsupported EasyEDA document type 1"));
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+}
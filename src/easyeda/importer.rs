@@ -1,4 +1,5 @@
 use crate::easyeda::models::*;
+use crate::easyeda::pro_importer::{ProFootprintImporter, ProSymbolImporter, is_pro_document};
 use crate::error::{EasyedaError, Result};
 
 pub struct SymbolImporter;
@@ -134,8 +135,8 @@ impl SymbolImporter {
                 "PT" => {
                     // Path (SVG path): PT~M x y L x y Z~color~width~...~fill
                     log::debug!("Parsing PT path with {} fields", fields.len());
-                    if let Ok(polygon) = Self::parse_pt_path(&fields) {
-                        symbol.polygons.push(polygon);
+                    if let Ok(path) = Self::parse_pt_path(&fields) {
+                        symbol.paths.push(path);
                     } else {
                         log::warn!("Failed to parse PT path from: {}", shape);
                     }
@@ -152,21 +153,14 @@ impl SymbolImporter {
                         symbol.paths.push(path);
                     }
                 }
-                "LIB" => {
-                    // Library info: LIB~x~y~package~id~locked
-                    if fields.len() > 3 {
-                        symbol.name = fields[3].to_string();
-                    }
+                // Library info: LIB~x~y~package~id~locked
+                "LIB" if fields.len() > 3 => {
+                    symbol.name = fields[3].to_string();
                 }
                 _ => {}
             }
         }
 
-        // Set default prefix if not found
-        if symbol.prefix.is_empty() {
-            symbol.prefix = "U".to_string();
-        }
-
         log::info!(
             "Parsed symbol: {} pins, {} rectangles, {} circles, {} ellipses, {} polylines",
             symbol.pins.len(),
@@ -216,30 +210,37 @@ impl SymbolImporter {
         // Extract electric type from field 2
         let electric_type = fields[2].to_string();
 
+        let segment = |i: usize| -> Vec<&str> {
+            segments
+                .get(i)
+                .map(|s| s.split('~').collect())
+                .unwrap_or_default()
+        };
+
         // Extract pin name from segment 3 if available
-        let name = if segments.len() > 3 {
-            let name_fields: Vec<&str> = segments[3].split('~').collect();
-            if name_fields.len() > 4 {
-                name_fields[4].to_string() // text field
-            } else {
-                "PIN".to_string()
-            }
+        let name_fields = segment(3);
+        let name = if name_fields.len() > 4 {
+            name_fields[4].to_string() // text field
         } else {
             "PIN".to_string()
         };
 
         // Extract pin number from segment 4 if available
-        let number = if segments.len() > 4 {
-            let num_fields: Vec<&str> = segments[4].split('~').collect();
-            if num_fields.len() > 4 {
-                num_fields[4].to_string()
-            } else {
-                fields[3].to_string()
-            }
+        let number_fields = segment(4);
+        let number = if number_fields.len() > 4 {
+            number_fields[4].to_string()
         } else {
             fields[3].to_string()
         };
 
+        // Name and number are shown unless their segment says otherwise
+        let name_visible = name_fields.first().is_none_or(|f| Self::is_shown(f));
+        let number_visible = number_fields.first().is_none_or(|f| Self::is_shown(f));
+
+        // Inversion bubble (segment 5) and clock wedge (segment 6)
+        let dot = segment(5).first().is_some_and(|f| Self::is_shown(f));
+        let clock = segment(6).first().is_some_and(|f| Self::is_shown(f));
+
         // Extract pin length from segment 2 (path) if available
         let length = if segments.len() > 2 {
             let path_fields: Vec<&str> = segments[2].split('~').collect();
@@ -296,14 +297,19 @@ impl SymbolImporter {
             y,
             rotation,
             length,
-            name_visible: true,
-            number_visible: true,
+            name_visible,
+            number_visible,
             electric_type,
-            dot: false,
-            clock: false,
+            dot,
+            clock,
         })
     }
 
+    /// Pin segment visibility flag: `1`/`show` when shown, `0`/`none`/empty when hidden
+    fn is_shown(flag: &str) -> bool {
+        matches!(flag.trim(), "1" | "show" | "true")
+    }
+
     fn parse_rectangle(fields: &[&str]) -> Result<EeRectangle> {
         if fields.len() < 7 {
             return Err(EasyedaError::InvalidData("Invalid rectangle data".to_string()).into());
@@ -505,7 +511,9 @@ impl SymbolImporter {
 
                     current_pos = (x, y);
                 }
-                SvgCommand::LineTo { x, y } => {
+                SvgCommand::LineTo { x, y }
+                | SvgCommand::CubicTo { x, y, .. }
+                | SvgCommand::QuadTo { x, y, .. } => {
                     current_pos = (x, y);
                 }
                 SvgCommand::ClosePath => {}
@@ -561,42 +569,16 @@ impl SymbolImporter {
         })
     }
 
-    fn parse_pt_path(fields: &[&str]) -> Result<EePolygon> {
+    fn parse_pt_path(fields: &[&str]) -> Result<EePath> {
         use crate::easyeda::svg_parser::{SvgCommand, parse_svg_path};
 
         if fields.len() < 2 {
             return Err(EasyedaError::InvalidData("Invalid path data".to_string()).into());
         }
 
-        let svg_path = fields[1];
-        let commands = parse_svg_path(svg_path)?;
-
-        let mut points = Vec::new();
-        let mut has_close_path = false;
-
-        for cmd in commands {
-            match cmd {
-                SvgCommand::MoveTo { x, y } => {
-                    points.push((x, y));
-                }
-                SvgCommand::LineTo { x, y } => {
-                    points.push((x, y));
-                }
-                SvgCommand::Arc { x, y, .. } => {
-                    // For paths, just add the end point
-                    points.push((x, y));
-                }
-                SvgCommand::ClosePath => {
-                    has_close_path = true;
-                }
-            }
-        }
-
-        // If path has ClosePath command (Z), close the polygon by adding the first point again
-        if has_close_path && !points.is_empty() {
-            let first_point = points[0];
-            points.push(first_point);
-        }
+        // Curves are kept as path data and flattened by the symbol converter
+        let path_data = fields[1].to_string();
+        let commands = parse_svg_path(&path_data)?;
 
         let stroke_width = if fields.len() > 3 {
             fields[3].parse::<f64>().unwrap_or(1.0)
@@ -606,10 +588,10 @@ impl SymbolImporter {
 
         // If path has ClosePath command (Z), it should be filled
         // This is typical for shapes like triangles in diode symbols
-        let fill = has_close_path;
+        let fill = commands.contains(&SvgCommand::ClosePath);
 
-        Ok(EePolygon {
-            points,
+        Ok(EePath {
+            path_data,
             stroke_width,
             fill,
         })
@@ -701,6 +683,7 @@ impl FootprintImporter {
             holes: Vec::new(),
             vias: Vec::new(),
             svg_nodes: Vec::new(),
+            solid_regions: Vec::new(),
         };
 
         for shape in shape_data {
@@ -753,8 +736,13 @@ impl FootprintImporter {
                     }
                 }
                 "SVGNODE" => {
-                    if let Ok(svg_node) = Self::parse_svg_node(&fields) {
-                        footprint.svg_nodes.push(svg_node);
+                    if let Ok(svg_nodes) = Self::parse_svg_node(&fields) {
+                        footprint.svg_nodes.extend(svg_nodes);
+                    }
+                }
+                "SOLIDREGION" => {
+                    if let Ok(region) = Self::parse_solid_region(&fields) {
+                        footprint.solid_regions.push(region);
                     }
                 }
                 _ => {}
@@ -838,6 +826,8 @@ impl FootprintImporter {
             hole_length,
             points,
             layer_id,
+            // Std pads have no corner radius; rounded and chamfered pads are polygons
+            corner_radius: 0.0,
         })
     }
 
@@ -986,6 +976,28 @@ impl FootprintImporter {
         })
     }
 
+    fn parse_solid_region(fields: &[&str]) -> Result<EeSolidRegion> {
+        if fields.len() < 4 {
+            return Err(EasyedaError::InvalidData("Invalid solid region data".to_string()).into());
+        }
+
+        // SOLIDREGION~layer_id~net~path~type~id~...~locked
+        let layer_id = fields[1]
+            .parse::<i32>()
+            .map_err(|_| EasyedaError::InvalidData("Invalid solid region layer_id".to_string()))?;
+        let path = fields[3].to_string();
+        let region_type = match fields.get(4).map(|t| t.trim().to_lowercase()) {
+            Some(t) if !t.is_empty() => t,
+            _ => "solid".to_string(),
+        };
+
+        Ok(EeSolidRegion {
+            layer_id,
+            path,
+            region_type,
+        })
+    }
+
     fn parse_hole(fields: &[&str]) -> Result<EeHole> {
         if fields.len() < 4 {
             return Err(EasyedaError::InvalidData("Invalid hole data".to_string()).into());
@@ -1033,18 +1045,167 @@ impl FootprintImporter {
         })
     }
 
-    fn parse_svg_node(fields: &[&str]) -> Result<EeSvgNode> {
-        if fields.len() < 3 {
+    fn parse_svg_node(fields: &[&str]) -> Result<Vec<EeSvgNode>> {
+        if fields.len() < 2 {
             return Err(EasyedaError::InvalidData("Invalid SVG node data".to_string()).into());
         }
 
-        let path = fields[2].to_string();
-        let layer = fields[1].to_string();
+        // SVGNODE~{"nodeName":"g","layerid":"19","attrs":{"c_etype":"outline3D",...},"childNodes":[...]}
+        let group: serde_json::Value = serde_json::from_str(&fields[1..].join("~"))
+            .map_err(|e| EasyedaError::InvalidData(format!("Invalid SVG node JSON: {}", e)))?;
+        let attr = |node: &serde_json::Value, key: &str| {
+            node.get("attrs")
+                .and_then(|attrs| attrs.get(key))
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string()
+        };
+        let layer = match group.get("layerid").and_then(|v| v.as_str()) {
+            Some(layer) => layer.to_string(),
+            None => attr(&group, "layerid"),
+        };
+        let node_type = attr(&group, "c_etype");
 
-        Ok(EeSvgNode {
-            path,
-            stroke_width: 1.0,
-            layer,
-        })
+        let mut nodes = Vec::new();
+        let mut pending = vec![&group];
+        while let Some(node) = pending.pop() {
+            if let Some(children) = node.get("childNodes").and_then(|v| v.as_array()) {
+                pending.extend(children.iter().rev());
+            }
+
+            let path = match node.get("nodeName").and_then(|v| v.as_str()) {
+                Some("path") => attr(node, "d"),
+                Some(name @ ("polyline" | "polygon")) => {
+                    let points = attr(node, "points");
+                    let coords: Vec<&str> =
+                        points.split([' ', ',']).filter(|c| !c.is_empty()).collect();
+                    let mut path: Vec<String> = coords
+                        .chunks_exact(2)
+                        .enumerate()
+                        .map(|(i, xy)| {
+                            format!("{} {} {}", if i == 0 { "M" } else { "L" }, xy[0], xy[1])
+                        })
+                        .collect();
+                    if name == "polygon" && !path.is_empty() {
+                        path.push("Z".to_string());
+                    }
+                    path.join(" ")
+                }
+                Some("circle") => {
+                    let num = |key: &str| attr(node, key).parse::<f64>().unwrap_or(0.0);
+                    let (cx, cy, r) = (num("cx"), num("cy"), num("r"));
+                    if r > 0.0 {
+                        format!(
+                            "M {} {} A {r} {r} 0 1 1 {} {} A {r} {r} 0 1 1 {} {} Z",
+                            cx - r,
+                            cy,
+                            cx + r,
+                            cy,
+                            cx - r,
+                            cy
+                        )
+                    } else {
+                        String::new()
+                    }
+                }
+                _ => String::new(),
+            };
+            if path.trim().is_empty() {
+                continue;
+            }
+
+            nodes.push(EeSvgNode {
+                path,
+                layer: layer.clone(),
+                node_type: node_type.clone(),
+            });
+        }
+
+        Ok(nodes)
+    }
+}
+
+/// Parse symbol shapes in either the EasyEDA Standard or Pro format
+pub fn import_symbol(data_str: &[String]) -> Result<EeSymbol> {
+    if is_pro_document(data_str) {
+        ProSymbolImporter::parse(data_str)
+    } else {
+        SymbolImporter::parse(data_str)
+    }
+}
+
+/// Parse footprint shapes in either the EasyEDA Standard or Pro format
+pub fn import_footprint(shape_data: &[String]) -> Result<EeFootprint> {
+    if is_pro_document(shape_data) {
+        ProFootprintImporter::parse(shape_data)
+    } else {
+        FootprintImporter::parse(shape_data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_pin_style_and_visibility_segments() {
+        let shapes = vec![
+            "P~show~0~1~400~300~180~gge1~0^^400~300^^M 400 300 h -20~#880000^^0~383~304~0~RST~start~~~#0000FF^^1~392~299~0~1~end~~~#0000FF^^1~377~300^^1~M 380 297 L 377 300 L 380 303".to_string(),
+            "P~show~0~2~400~310~180~gge2~0^^400~310^^M 400 310 h -20~#880000^^1~383~314~0~SDA~start~~~#0000FF^^1~392~309~0~2~end~~~#0000FF^^0~377~310^^0~M 380 307 L 377 310 L 380 313".to_string(),
+        ];
+
+        let symbol = import_symbol(&shapes).unwrap();
+        let rst = &symbol.pins[0];
+        assert_eq!(rst.name, "RST");
+        assert!(rst.dot && rst.clock);
+        assert!(!rst.name_visible && rst.number_visible);
+
+        let sda = &symbol.pins[1];
+        assert!(!sda.dot && !sda.clock);
+        assert!(sda.name_visible);
+    }
+
+    #[test]
+    fn parses_solid_regions() {
+        let shapes = vec![
+            "SOLIDREGION~1~GND~M 390 290 L 410 290 L 410 310 L 390 310 Z ~solid~gge5~~~~0"
+                .to_string(),
+            "SOLIDREGION~11~~M 380 280 L 385 280 L 385 285 Z~NPTH~gge6~~~~0".to_string(),
+            "SOLIDREGION~2~~M 380 280 L 385 280 L 385 285 Z".to_string(),
+        ];
+
+        let footprint = import_footprint(&shapes).unwrap();
+        let types: Vec<(i32, &str)> = footprint
+            .solid_regions
+            .iter()
+            .map(|r| (r.layer_id, r.region_type.as_str()))
+            .collect();
+        assert_eq!(types, [(1, "solid"), (11, "npth"), (2, "solid")]);
+        assert_eq!(
+            footprint.solid_regions[0].path,
+            "M 390 290 L 410 290 L 410 310 L 390 310 Z "
+        );
+    }
+
+    #[test]
+    fn parses_svg_node_shapes() {
+        let shapes = vec![
+            r#"SVGNODE~{"gId":"g1","nodeName":"g","nodeType":1,"layerid":"19","attrs":{"c_etype":"outline3D","uuid":"8d1d","title":"SOT-23"},"childNodes":[{"gId":"g1_1","nodeName":"polyline","nodeType":1,"layerid":"19","attrs":{"fill":"none","points":"3990 2990 4010 2990 4010 3000 3990 3000 3990 2990","stroke-width":"0.5"},"childNodes":null},{"gId":"g1_2","nodeName":"path","nodeType":1,"layerid":"19","attrs":{"d":"M 3995 2995 L 4005 2995"},"childNodes":null}]}"#
+                .to_string(),
+        ];
+
+        let footprint = import_footprint(&shapes).unwrap();
+        let nodes = &footprint.svg_nodes;
+        assert_eq!(nodes.len(), 2);
+        assert_eq!(
+            nodes[0].path,
+            "M 3990 2990 L 4010 2990 L 4010 3000 L 3990 3000 L 3990 2990"
+        );
+        assert_eq!(nodes[1].path, "M 3995 2995 L 4005 2995");
+        assert!(
+            nodes
+                .iter()
+                .all(|n| n.layer == "19" && n.node_type == "outline3D")
+        );
     }
 }
@@ -1,9 +1,23 @@
 pub mod api;
+pub mod cache;
+pub mod config;
+pub mod export_file;
 pub mod importer;
 pub mod models;
+pub mod pro_importer;
+pub mod retry;
+pub mod source;
 pub mod svg_parser;
 
 pub use api::EasyedaApi;
-pub use importer::{FootprintImporter, SymbolImporter};
+pub use cache::CachedSource;
+pub use config::EasyedaApiConfig;
+pub use export_file::{exported_part_id, load_exported_component};
+pub use importer::{FootprintImporter, SymbolImporter, import_footprint, import_symbol};
 pub use models::*;
+pub use pro_importer::{ProFootprintImporter, ProSymbolImporter, is_pro_document};
+pub use retry::{RateLimiter, RetryPolicy};
+pub use source::{
+    ChainedSource, ComponentSource, LocalSource, source_from_cli, source_with_upstream,
+};
 pub use svg_parser::parse_svg_path;
@@ -1,3 +1,4 @@
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -15,18 +16,58 @@ pub struct ComponentData {
     pub manufacturer: String,
     pub datasheet: String,
     pub jlc_id: String,
+    /// Symbol shapes of each unit of a multi-unit part; empty for single-unit parts
+    #[serde(default)]
+    pub sub_parts: Vec<SymbolPart>,
+    /// Symbol `head.c_para` parameters in source order, values as strings
+    #[serde(default)]
+    pub c_para: IndexMap<String, String>,
+    /// LCSC/JLC category path (e.g. "Resistors / Chip Resistor - Surface Mount"), if known
+    #[serde(default)]
+    pub category: String,
+}
+
+/// One sub-part (unit) of a multi-unit symbol
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SymbolPart {
+    pub data_str: Vec<String>,
+    pub bbox_x: f64,
+    pub bbox_y: f64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Model3dInfo {
     pub uuid: String,
     pub title: String,
+    /// Model origin on the footprint canvas (`c_origin`), in px
+    #[serde(default)]
+    pub origin: Option<(f64, f64)>,
+    /// Height above the board (`z`), in px
+    #[serde(default)]
+    pub z: f64,
+    /// Rotation about X, Y and Z in degrees (`c_rotation`)
+    #[serde(default)]
+    pub rotation: (f64, f64, f64),
+}
+
+/// One part returned by the keyword search endpoint
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchResult {
+    pub lcsc_id: String,
+    pub mpn: String,
+    pub manufacturer: String,
+    pub package: String,
+    /// `None` until the component data has been looked up
+    pub has_3d_model: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiResponse {
     pub success: bool,
     pub result: Option<ApiResult>,
+    /// Fields nlbn does not read, kept so the response round-trips unchanged
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,12 +79,18 @@ pub struct ApiResult {
     #[serde(rename = "packageDetail")]
     pub package_detail: Option<serde_json::Value>,
     pub lcsc: Option<serde_json::Value>,
+    /// Unit documents of multi-unit parts, each shaped like `dataStr`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subparts: Option<serde_json::Value>,
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 // EasyEDA Symbol structures
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct EeSymbol {
     pub name: String,
+    /// Designator prefix from the document, without `?`; empty when the document has none
     pub prefix: String,
     pub pins: Vec<EePin>,
     pub rectangles: Vec<EeRectangle>,
@@ -56,7 +103,7 @@ pub struct EeSymbol {
     pub texts: Vec<EeText>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct EePin {
     pub number: String,
     pub name: String,
@@ -71,7 +118,7 @@ pub struct EePin {
     pub clock: bool,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct EeRectangle {
     pub x: f64,
     pub y: f64,
@@ -82,7 +129,7 @@ pub struct EeRectangle {
     pub layer_id: i32,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct EeCircle {
     pub cx: f64,
     pub cy: f64,
@@ -92,7 +139,7 @@ pub struct EeCircle {
     pub layer_id: i32,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct EeEllipse {
     pub cx: f64,
     pub cy: f64,
@@ -102,7 +149,7 @@ pub struct EeEllipse {
     pub fill: bool,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct EeArc {
     pub x: f64,
     pub y: f64,
@@ -112,34 +159,34 @@ pub struct EeArc {
     pub stroke_width: f64,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct EeFootprintArc {
     pub stroke_width: f64,
     pub layer_id: i32,
     pub path: String, // SVG path string: "M startX startY A rx ry rotation large_arc sweep endX endY"
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct EePolyline {
     pub points: Vec<(f64, f64)>,
     pub stroke_width: f64,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct EePolygon {
     pub points: Vec<(f64, f64)>,
     pub stroke_width: f64,
     pub fill: bool,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct EePath {
     pub path_data: String, // SVG path string (e.g., "M 0,0 L 10,10")
     pub stroke_width: f64,
     pub fill: bool,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct EeText {
     pub text: String,
     pub x: f64,
@@ -151,7 +198,7 @@ pub struct EeText {
 }
 
 // EasyEDA Footprint structures
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct EeFootprint {
     pub name: String,
     pub pads: Vec<EePad>,
@@ -163,9 +210,10 @@ pub struct EeFootprint {
     pub holes: Vec<EeHole>,
     pub vias: Vec<EeVia>,
     pub svg_nodes: Vec<EeSvgNode>,
+    pub solid_regions: Vec<EeSolidRegion>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct EePad {
     pub number: String,
     pub shape: String,
@@ -178,9 +226,11 @@ pub struct EePad {
     pub hole_length: Option<f64>, // For elliptical drills
     pub points: String,           // For polygon pads
     pub layer_id: i32,
+    /// Corner radius of RECT pads, 0 for sharp corners
+    pub corner_radius: f64,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct EeTrack {
     pub stroke_width: f64,
     pub layer_id: i32,
@@ -188,14 +238,22 @@ pub struct EeTrack {
     pub points: String, // Space-separated coordinates: "x1 y1 x2 y2 x3 y3..."
 }
 
-#[derive(Debug, Clone)]
+/// Filled area, copper-pour cutout or board cutout
+#[derive(Debug, Clone, Serialize)]
+pub struct EeSolidRegion {
+    pub layer_id: i32,
+    pub path: String,        // SVG path of the outline
+    pub region_type: String, // "solid", "cutout" or "npth"
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct EeHole {
     pub x: f64,
     pub y: f64,
     pub radius: f64, // EasyEDA stores radius, not diameter
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct EeVia {
     pub x: f64,
     pub y: f64,
@@ -204,9 +262,10 @@ pub struct EeVia {
     pub radius: f64, // Hole radius (drill = radius * 2)
 }
 
-#[derive(Debug, Clone)]
+/// One shape of an `SVGNODE` group, such as the `outline3D` body outline
+#[derive(Debug, Clone, Serialize)]
 pub struct EeSvgNode {
-    pub path: String,
-    pub stroke_width: f64,
-    pub layer: String,
+    pub path: String,      // SVG path of the shape
+    pub layer: String,     // Layer id of the group, "19" for the 3D model
+    pub node_type: String, // c_etype of the group, e.g. "outline3D"
 }
@@ -0,0 +1,661 @@
+//! Importer for the EasyEDA Pro (v2) document format.
+//!
+//! Pro documents are JSON lines such as `["PIN","e4",1,null,-40,10,10,180,...]`.
+//! They are converted into the same `EeSymbol`/`EeFootprint` models as the
+//! Standard importer, so coordinates are mapped into Standard conventions:
+//! Y pointing down, symbol units of 10 mil and footprint units of 10 mil
+//! (Pro footprints use 1 mil units).
+
+use crate::easyeda::models::*;
+use crate::error::{EasyedaError, Result};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// True if the lines look like an EasyEDA Pro document (`["DOCTYPE", ...]` header)
+pub fn is_pro_document(lines: &[String]) -> bool {
+    lines
+        .iter()
+        .map(|l| l.trim())
+        .find(|l| !l.is_empty())
+        .map(|l| l.starts_with('[') && l.contains("\"DOCTYPE\""))
+        .unwrap_or(false)
+}
+
+fn parse_records(lines: &[String]) -> Vec<Vec<Value>> {
+    lines
+        .iter()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty())
+        .filter_map(|l| match serde_json::from_str::<Value>(l) {
+            Ok(Value::Array(fields)) if !fields.is_empty() => Some(fields),
+            _ => {
+                log::debug!("Skipping unparsable Pro record: {}", l);
+                None
+            }
+        })
+        .collect()
+}
+
+fn num(fields: &[Value], index: usize) -> Result<f64> {
+    fields
+        .get(index)
+        .and_then(|v| v.as_f64().or_else(|| v.as_str()?.parse().ok()))
+        .ok_or_else(|| {
+            EasyedaError::InvalidData(format!(
+                "Expected number at field {} of {}",
+                index,
+                fields.first().and_then(|v| v.as_str()).unwrap_or("record")
+            ))
+            .into()
+        })
+}
+
+fn text(fields: &[Value], index: usize) -> String {
+    match fields.get(index) {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Number(n)) => n.to_string(),
+        _ => String::new(),
+    }
+}
+
+fn flag(fields: &[Value], index: usize) -> bool {
+    match fields.get(index) {
+        Some(Value::Bool(b)) => *b,
+        Some(Value::Number(n)) => n.as_f64().unwrap_or(0.0) != 0.0,
+        _ => false,
+    }
+}
+
+/// Flat `[x1, y1, x2, y2, ...]` list of numbers
+fn point_list(value: Option<&Value>) -> Vec<(f64, f64)> {
+    let coords: Vec<f64> = value
+        .and_then(|v| v.as_array())
+        .map(|a| a.iter().filter_map(|v| v.as_f64()).collect())
+        .unwrap_or_default();
+    coords.chunks_exact(2).map(|c| (c[0], c[1])).collect()
+}
+
+pub struct ProSymbolImporter;
+
+impl ProSymbolImporter {
+    pub fn parse(lines: &[String]) -> Result<EeSymbol> {
+        let records = parse_records(lines);
+        log::debug!("Parsing Pro symbol with {} records", records.len());
+
+        let mut symbol = EeSymbol {
+            name: String::new(),
+            prefix: String::new(),
+            pins: Vec::new(),
+            rectangles: Vec::new(),
+            circles: Vec::new(),
+            ellipses: Vec::new(),
+            arcs: Vec::new(),
+            polylines: Vec::new(),
+            polygons: Vec::new(),
+            paths: Vec::new(),
+            texts: Vec::new(),
+        };
+
+        // Pin names, numbers and types are ATTR records that point at the pin id
+        let mut pin_attrs: HashMap<String, HashMap<String, String>> = HashMap::new();
+        for fields in &records {
+            if text(fields, 0) == "ATTR" {
+                // ATTR~id~parent_id~key~value~key_visible~value_visible~x~y~rotation~style~locked
+                let parent = text(fields, 2);
+                let key = text(fields, 3);
+                let value = text(fields, 4);
+                if parent.is_empty() {
+                    match key.as_str() {
+                        "Designator" => {
+                            symbol.prefix = value.trim_end_matches('?').to_string();
+                        }
+                        "Symbol" | "Name" if symbol.name.is_empty() => symbol.name = value,
+                        _ => {}
+                    }
+                } else {
+                    pin_attrs.entry(parent).or_default().insert(key, value);
+                }
+            }
+        }
+
+        for fields in &records {
+            let designator = text(fields, 0);
+            let result = match designator.as_str() {
+                "PIN" => Self::parse_pin(fields, &pin_attrs).map(|p| symbol.pins.push(p)),
+                "RECT" => Self::parse_rectangle(fields).map(|r| symbol.rectangles.push(r)),
+                "CIRCLE" => Self::parse_circle(fields).map(|c| symbol.circles.push(c)),
+                "ELLIPSE" => Self::parse_ellipse(fields).map(|e| symbol.ellipses.push(e)),
+                "ARC" => Self::parse_arc(fields).map(|a| symbol.arcs.push(a)),
+                "POLY" => Self::parse_poly(fields, &mut symbol),
+                "TEXT" => Self::parse_text(fields).map(|t| symbol.texts.push(t)),
+                _ => Ok(()),
+            };
+            if let Err(e) = result {
+                log::warn!("Failed to parse Pro {} record: {}", designator, e);
+            }
+        }
+
+        log::info!(
+            "Parsed Pro symbol: {} pins, {} rectangles, {} circles, {} ellipses, {} polylines",
+            symbol.pins.len(),
+            symbol.rectangles.len(),
+            symbol.circles.len(),
+            symbol.ellipses.len(),
+            symbol.polylines.len()
+        );
+
+        Ok(symbol)
+    }
+
+    fn parse_pin(
+        fields: &[Value],
+        pin_attrs: &HashMap<String, HashMap<String, String>>,
+    ) -> Result<EePin> {
+        // PIN~id~display~electric~x~y~length~rotation~color~shape~...
+        let id = text(fields, 1);
+        let x = num(fields, 4)?;
+        let y = num(fields, 5)?;
+        let length = num(fields, 6).unwrap_or(10.0).abs();
+        // Pro rotation is the direction the pin body points from its connection
+        // end; Standard rotation is the opposite direction
+        let rotation = (num(fields, 7).unwrap_or(0.0) as i32 + 180).rem_euclid(360);
+        // Pin shape: 0 = line, 1 = clock, 2 = inverted, 3 = inverted clock
+        let shape = num(fields, 9).unwrap_or(0.0) as i32;
+
+        let attrs = pin_attrs.get(&id);
+        let attr = |key: &str| attrs.and_then(|a| a.get(key)).cloned();
+        let number = attr("NUMBER").unwrap_or_default();
+        let name = attr("NAME").unwrap_or_else(|| number.clone());
+        let electric_type = attr("Pin Type")
+            .map(|t| Self::electric_type(&t))
+            .unwrap_or("U")
+            .to_string();
+
+        Ok(EePin {
+            number,
+            name,
+            x,
+            y: -y,
+            rotation,
+            length,
+            name_visible: true,
+            number_visible: true,
+            electric_type,
+            dot: shape == 2 || shape == 3,
+            clock: shape == 1 || shape == 3,
+        })
+    }
+
+    fn electric_type(pin_type: &str) -> &'static str {
+        match pin_type.to_ascii_uppercase().as_str() {
+            "IN" | "INPUT" => "I",
+            "OUT" | "OUTPUT" => "O",
+            "BI" | "BIDIRECTIONAL" | "IO" => "B",
+            "TRI" | "TRISTATE" => "T",
+            "PASSIVE" => "P",
+            "POWER" | "POWER IN" => "W",
+            "POWER OUT" => "w",
+            "OPEN COLLECTOR" => "C",
+            "OPEN EMITTER" => "E",
+            "NC" | "NO CONNECT" => "N",
+            _ => "U",
+        }
+    }
+
+    fn parse_rectangle(fields: &[Value]) -> Result<EeRectangle> {
+        // RECT~id~x1~y1~x2~y2~rx~ry~rotation~style~locked
+        let x1 = num(fields, 2)?;
+        let y1 = -num(fields, 3)?;
+        let x2 = num(fields, 4)?;
+        let y2 = -num(fields, 5)?;
+
+        Ok(EeRectangle {
+            x: x1.min(x2),
+            y: y1.min(y2),
+            width: (x2 - x1).abs(),
+            height: (y2 - y1).abs(),
+            stroke_width: 1.0,
+            fill: false,
+            layer_id: 0,
+        })
+    }
+
+    fn parse_circle(fields: &[Value]) -> Result<EeCircle> {
+        // CIRCLE~id~cx~cy~radius~style~locked
+        Ok(EeCircle {
+            cx: num(fields, 2)?,
+            cy: -num(fields, 3)?,
+            radius: num(fields, 4)?,
+            stroke_width: 1.0,
+            fill: false,
+            layer_id: 0,
+        })
+    }
+
+    fn parse_ellipse(fields: &[Value]) -> Result<EeEllipse> {
+        // ELLIPSE~id~cx~cy~rx~ry~rotation~style~locked
+        Ok(EeEllipse {
+            cx: num(fields, 2)?,
+            cy: -num(fields, 3)?,
+            rx: num(fields, 4)?,
+            ry: num(fields, 5)?,
+            stroke_width: 1.0,
+            fill: false,
+        })
+    }
+
+    fn parse_arc(fields: &[Value]) -> Result<EeArc> {
+        // ARC~id~start_x~start_y~mid_x~mid_y~end_x~end_y~style~locked
+        let start = (num(fields, 2)?, -num(fields, 3)?);
+        let mid = (num(fields, 4)?, -num(fields, 5)?);
+        let end = (num(fields, 6)?, -num(fields, 7)?);
+
+        let (cx, cy, radius) = circle_through(start, mid, end)
+            .ok_or_else(|| EasyedaError::InvalidData("Arc points are collinear".to_string()))?;
+
+        // Pick the signed sweep that passes through the mid point, so the
+        // average of start and end angle lands on the arc
+        let angle = |p: (f64, f64)| (p.1 - cy).atan2(p.0 - cx).to_degrees();
+        let start_angle = angle(start);
+        let mut sweep = (angle(end) - start_angle).rem_euclid(360.0);
+        if (angle(mid) - start_angle).rem_euclid(360.0) > sweep {
+            sweep -= 360.0;
+        }
+
+        Ok(EeArc {
+            x: cx,
+            y: cy,
+            radius,
+            start_angle,
+            end_angle: start_angle + sweep,
+            stroke_width: 1.0,
+        })
+    }
+
+    fn parse_poly(fields: &[Value], symbol: &mut EeSymbol) -> Result<()> {
+        // POLY~id~[x1,y1,x2,y2,...]~closed~style~locked
+        let points: Vec<(f64, f64)> = point_list(fields.get(2))
+            .into_iter()
+            .map(|(x, y)| (x, -y))
+            .collect();
+        if points.len() < 2 {
+            return Err(EasyedaError::InvalidData("Polyline needs two points".to_string()).into());
+        }
+
+        if flag(fields, 3) {
+            symbol.polygons.push(EePolygon {
+                points,
+                stroke_width: 1.0,
+                fill: false,
+            });
+        } else {
+            symbol.polylines.push(EePolyline {
+                points,
+                stroke_width: 1.0,
+            });
+        }
+        Ok(())
+    }
+
+    fn parse_text(fields: &[Value]) -> Result<EeText> {
+        // TEXT~id~x~y~rotation~text~style~locked
+        Ok(EeText {
+            text: text(fields, 5),
+            x: num(fields, 2)?,
+            y: -num(fields, 3)?,
+            rotation: num(fields, 4).unwrap_or(0.0) as i32,
+            font_size: 7.0,
+            stroke_width: 0.0,
+            layer_id: 0,
+        })
+    }
+}
+
+/// Center and radius of the circle through three points
+fn circle_through(a: (f64, f64), b: (f64, f64), c: (f64, f64)) -> Option<(f64, f64, f64)> {
+    let d = 2.0 * (a.0 * (b.1 - c.1) + b.0 * (c.1 - a.1) + c.0 * (a.1 - b.1));
+    if d.abs() < 1e-10 {
+        return None;
+    }
+    let a2 = a.0 * a.0 + a.1 * a.1;
+    let b2 = b.0 * b.0 + b.1 * b.1;
+    let c2 = c.0 * c.0 + c.1 * c.1;
+    let cx = (a2 * (b.1 - c.1) + b2 * (c.1 - a.1) + c2 * (a.1 - b.1)) / d;
+    let cy = (a2 * (c.0 - b.0) + b2 * (a.0 - c.0) + c2 * (b.0 - a.0)) / d;
+    let radius = ((a.0 - cx).powi(2) + (a.1 - cy).powi(2)).sqrt();
+    Some((cx, cy, radius))
+}
+
+pub struct ProFootprintImporter;
+
+impl ProFootprintImporter {
+    /// Pro footprints use 1 mil units; Standard uses 10 mil
+    const MIL_TO_PX: f64 = 0.1;
+
+    pub fn parse(lines: &[String]) -> Result<EeFootprint> {
+        let records = parse_records(lines);
+        log::debug!("Parsing Pro footprint with {} records", records.len());
+
+        let mut footprint = EeFootprint {
+            name: String::new(),
+            pads: Vec::new(),
+            tracks: Vec::new(),
+            circles: Vec::new(),
+            arcs: Vec::new(),
+            rectangles: Vec::new(),
+            texts: Vec::new(),
+            holes: Vec::new(),
+            vias: Vec::new(),
+            svg_nodes: Vec::new(),
+            solid_regions: Vec::new(),
+        };
+
+        for fields in &records {
+            let designator = text(fields, 0);
+            let result = match designator.as_str() {
+                "PAD" => Self::parse_pad(fields).map(|p| footprint.pads.push(p)),
+                "POLY" => Self::parse_poly(fields, &mut footprint),
+                "STRING" => Self::parse_string(fields).map(|t| footprint.texts.push(t)),
+                _ => Ok(()),
+            };
+            if let Err(e) = result {
+                log::warn!("Failed to parse Pro {} record: {}", designator, e);
+            }
+        }
+
+        Ok(footprint)
+    }
+
+    fn px(mil: f64) -> f64 {
+        mil * Self::MIL_TO_PX
+    }
+
+    /// Map Pro PCB layer ids onto the Standard ids understood by `kicad::map_layer`
+    fn layer(pro_layer: i32) -> i32 {
+        match pro_layer {
+            1 => 1,        // Top copper
+            2 => 2,        // Bottom copper
+            3 => 3,        // Top silk
+            4 => 4,        // Bottom silk
+            5 => 7,        // Top solder mask
+            6 => 8,        // Bottom solder mask
+            7 => 5,        // Top paste
+            8 => 6,        // Bottom paste
+            11 => 10,      // Board outline
+            12 => 11,      // Multi-layer
+            13 => 12,      // Document
+            48 | 49 => 13, // Component shape / marking
+            other => other,
+        }
+    }
+
+    fn parse_pad(fields: &[Value]) -> Result<EePad> {
+        // PAD~id~group~net~layer~number~x~y~rotation~hole~shape~...
+        let layer_id = Self::layer(num(fields, 4)? as i32);
+        let number = text(fields, 5);
+        let x = Self::px(num(fields, 6)?);
+        let y = -Self::px(num(fields, 7)?);
+        let rotation = num(fields, 8).unwrap_or(0.0);
+
+        // Hole: null or ["ROUND"|"SLOT", width, height]
+        let (hole_radius, hole_length) = match fields.get(9).and_then(|v| v.as_array()) {
+            Some(hole) if hole.len() >= 3 => {
+                let w = Self::px(num(hole, 1)?);
+                let h = Self::px(num(hole, 2)?);
+                let length = if (w - h).abs() > 1e-6 {
+                    Some(w.max(h))
+                } else {
+                    None
+                };
+                (Some(w.min(h) / 2.0), length)
+            }
+            _ => (None, None),
+        };
+
+        // Shape: ["RECT", w, h, corner_radius], ["ELLIPSE", w, h], ["OVAL", w, h], ["POLY", [...]]
+        let shape_def = fields
+            .get(10)
+            .and_then(|v| v.as_array())
+            .ok_or_else(|| EasyedaError::InvalidData("Missing pad shape".to_string()))?;
+        let kind = text(shape_def, 0);
+        let (shape, width, height, points, corner_radius) = match kind.as_str() {
+            "POLY" => {
+                let points = point_list(shape_def.get(1))
+                    .into_iter()
+                    .map(|(px, py)| format!("{} {}", x + Self::px(px), y - Self::px(py)))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                ("POLYGON".to_string(), 0.0, 0.0, points, 0.0)
+            }
+            "ELLIPSE" | "OVAL" | "RECT" => (
+                kind.clone(),
+                Self::px(num(shape_def, 1)?),
+                Self::px(num(shape_def, 2)?),
+                String::new(),
+                Self::px(num(shape_def, 3).unwrap_or(0.0)),
+            ),
+            other => {
+                return Err(
+                    EasyedaError::InvalidData(format!("Unknown pad shape {}", other)).into(),
+                );
+            }
+        };
+
+        Ok(EePad {
+            number,
+            shape,
+            x,
+            y,
+            width,
+            height,
+            rotation,
+            hole_radius,
+            hole_length,
+            points,
+            layer_id,
+            corner_radius,
+        })
+    }
+
+    fn parse_poly(fields: &[Value], footprint: &mut EeFootprint) -> Result<()> {
+        // POLY~id~group~net~layer~width~path~locked
+        // path: [x, y, "L", x, y, ..., "ARC", angle, x, y, ...], ["CIRCLE", cx, cy, r]
+        // or ["R", x, y, w, h, ...]
+        let layer_id = Self::layer(num(fields, 4)? as i32);
+        let stroke_width = Self::px(num(fields, 5).unwrap_or(10.0));
+        let path = fields
+            .get(6)
+            .and_then(|v| v.as_array())
+            .ok_or_else(|| EasyedaError::InvalidData("Missing POLY path".to_string()))?;
+
+        match path.first().and_then(|v| v.as_str()) {
+            Some("CIRCLE") => {
+                footprint.circles.push(EeCircle {
+                    cx: Self::px(num(path, 1)?),
+                    cy: -Self::px(num(path, 2)?),
+                    radius: Self::px(num(path, 3)?),
+                    stroke_width,
+                    fill: false,
+                    layer_id,
+                });
+                return Ok(());
+            }
+            Some("R") => {
+                let x = Self::px(num(path, 1)?);
+                let y = -Self::px(num(path, 2)?);
+                footprint.rectangles.push(EeRectangle {
+                    x,
+                    y,
+                    width: Self::px(num(path, 3)?),
+                    height: Self::px(num(path, 4)?),
+                    stroke_width,
+                    fill: false,
+                    layer_id,
+                });
+                return Ok(());
+            }
+            _ => {}
+        }
+
+        let mut points: Vec<(f64, f64)> = Vec::new();
+        let flush = |points: &mut Vec<(f64, f64)>, footprint: &mut EeFootprint| {
+            if points.len() >= 2 {
+                footprint.tracks.push(EeTrack {
+                    stroke_width,
+                    layer_id,
+                    net: String::new(),
+                    points: points
+                        .iter()
+                        .map(|(x, y)| format!("{} {}", x, y))
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                });
+            }
+            points.clear();
+        };
+
+        let mut i = 0;
+        while i < path.len() {
+            match &path[i] {
+                Value::String(cmd) if cmd == "L" => i += 1,
+                Value::String(cmd) if cmd == "ARC" || cmd == "CARC" => {
+                    // ARC~angle~end_x~end_y, angle counter-clockwise in degrees
+                    let sweep = num(path, i + 1)?;
+                    let end = (Self::px(num(path, i + 2)?), -Self::px(num(path, i + 3)?));
+                    if let Some(&start) = points.last() {
+                        let chord = ((end.0 - start.0).powi(2) + (end.1 - start.1).powi(2)).sqrt();
+                        let half = (sweep.to_radians() / 2.0).sin().abs();
+                        if half > 1e-9 {
+                            let radius = chord / (2.0 * half);
+                            // Counter-clockwise in Y-up becomes clockwise with Y down
+                            footprint.arcs.push(EeFootprintArc {
+                                stroke_width,
+                                layer_id,
+                                path: format!(
+                                    "M {} {} A {} {} 0 {} {} {} {}",
+                                    start.0,
+                                    start.1,
+                                    radius,
+                                    radius,
+                                    if sweep.abs() > 180.0 { 1 } else { 0 },
+                                    if sweep < 0.0 { 1 } else { 0 },
+                                    end.0,
+                                    end.1
+                                ),
+                            });
+                        }
+                    }
+                    flush(&mut points, footprint);
+                    points.push(end);
+                    i += 4;
+                }
+                Value::Number(_) => {
+                    let x = Self::px(num(path, i)?);
+                    let y = -Self::px(num(path, i + 1)?);
+                    points.push((x, y));
+                    i += 2;
+                }
+                _ => i += 1,
+            }
+        }
+        flush(&mut points, footprint);
+
+        Ok(())
+    }
+
+    fn parse_string(fields: &[Value]) -> Result<EeText> {
+        // STRING~id~group~layer~x~y~text~font~height~stroke_width~...~rotation
+        Ok(EeText {
+            text: text(fields, 6),
+            x: Self::px(num(fields, 4)?),
+            y: -Self::px(num(fields, 5)?),
+            rotation: num(fields, 12).unwrap_or(0.0) as i32,
+            font_size: Self::px(num(fields, 8).unwrap_or(40.0)),
+            stroke_width: Self::px(num(fields, 9).unwrap_or(6.0)),
+            layer_id: Self::layer(num(fields, 3).unwrap_or(3.0) as i32),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(doc: &str) -> Vec<String> {
+        doc.lines().map(|l| l.to_string()).collect()
+    }
+
+    #[test]
+    fn detects_pro_documents() {
+        assert!(is_pro_document(&lines(
+            "\n[\"DOCTYPE\",\"SYMBOL\",\"1.1\"]\n[\"HEAD\",{}]"
+        )));
+        assert!(!is_pro_document(&lines("LIB~0~0~package`R0603`~~~")));
+    }
+
+    #[test]
+    fn parses_pro_symbol() {
+        let symbol = ProSymbolImporter::parse(&lines(
+            r#"["DOCTYPE","SYMBOL","1.1"]
+["RECT","e1",-20,20,20,-20,0,0,0,"st1",0]
+["PIN","e2",1,null,-40,10,20,0,null,2,0]
+["ATTR","e3","e2","NAME","~RESET",false,true]
+["ATTR","e4","e2","NUMBER","1",false,true]
+["ATTR","e5","e2","Pin Type","IN",false,false]
+["ATTR","e6","","Designator","U?",true,true]
+["ARC","e7",0,10,10,0,0,-10,"st1",0]
+["POLY","e8",[0,0,10,0,10,10],true,"st1",0]"#,
+        ))
+        .unwrap();
+
+        assert_eq!(symbol.prefix, "U");
+        assert_eq!(symbol.rectangles.len(), 1);
+        assert_eq!(symbol.rectangles[0].y, -20.0);
+        assert_eq!(symbol.rectangles[0].width, 40.0);
+
+        let pin = &symbol.pins[0];
+        assert_eq!(pin.name, "~RESET");
+        assert_eq!(pin.number, "1");
+        assert_eq!(pin.electric_type, "I");
+        assert_eq!((pin.x, pin.y), (-40.0, -10.0));
+        assert_eq!(pin.rotation, 180);
+        assert!(pin.dot && !pin.clock);
+
+        let arc = &symbol.arcs[0];
+        assert!((arc.radius - 10.0).abs() < 1e-9);
+        let mid = ((arc.start_angle + arc.end_angle) / 2.0).to_radians();
+        assert!((arc.x + arc.radius * mid.cos() - 10.0).abs() < 1e-9);
+
+        assert_eq!(symbol.polygons.len(), 1);
+        assert_eq!(symbol.polygons[0].points[2], (10.0, -10.0));
+    }
+
+    #[test]
+    fn parses_pro_footprint() {
+        let footprint = ProFootprintImporter::parse(&lines(
+            r#"["DOCTYPE","FOOTPRINT","1.8"]
+["PAD","e1",0,"",1,"1",-30,0,90,null,["RECT",40,50,10],[],0,0,null,1,0,2,2,0,0]
+["PAD","e2",0,"",12,"2",30,0,0,["ROUND",30,30],["ELLIPSE",60,60],[],0,0,null,1,0,2,2,0,0]
+["POLY","e3",0,"",3,8,[-50,25,"L",50,25,"ARC",180,50,-25],0]
+["POLY","e4",0,"",3,8,["CIRCLE",0,0,10],0]"#,
+        ))
+        .unwrap();
+
+        assert_eq!(footprint.pads.len(), 2);
+        let smd = &footprint.pads[0];
+        assert_eq!((smd.x, smd.width, smd.height), (-3.0, 4.0, 5.0));
+        assert_eq!(smd.layer_id, 1);
+        assert!(smd.hole_radius.is_none());
+        assert_eq!(smd.corner_radius, 1.0);
+
+        let tht = &footprint.pads[1];
+        assert_eq!(tht.layer_id, 11);
+        assert_eq!(tht.hole_radius, Some(1.5));
+
+        assert_eq!(footprint.tracks.len(), 1);
+        assert_eq!(footprint.tracks[0].points, "-5 -2.5 5 -2.5");
+        assert_eq!(footprint.arcs.len(), 1);
+        assert_eq!(footprint.circles.len(), 1);
+        assert_eq!(footprint.circles[0].radius, 1.0);
+    }
+}
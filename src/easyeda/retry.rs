@@ -0,0 +1,210 @@
+use reqwest::StatusCode;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// Retry schedule shared by every EasyEDA request: exponential backoff with
+/// jitter, overridden by the server's `Retry-After` when it sends one.
+/// Either way a single wait never exceeds `max_delay`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Total attempts, including the first one
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before the attempt following `attempt` (1-based).
+    ///
+    /// The backoff doubles each attempt up to `max_delay` and is jittered into
+    /// `[backoff / 2, backoff]` so parallel workers do not retry in lockstep.
+    /// A server `Retry-After` is honoured up to `max_delay`.
+    pub fn delay(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        if let Some(retry_after) = retry_after {
+            return retry_after.min(self.max_delay);
+        }
+
+        let exp = attempt.saturating_sub(1).min(16);
+        let backoff = self
+            .base_delay
+            .saturating_mul(1 << exp)
+            .min(self.max_delay)
+            .as_secs_f64();
+        Duration::from_secs_f64(backoff * rand::random_range(0.5..=1.0))
+    }
+
+    /// 408, 429 and 5xx are worth another attempt; other errors are final
+    pub fn is_retryable(status: StatusCode) -> bool {
+        status == StatusCode::REQUEST_TIMEOUT
+            || status == StatusCode::TOO_MANY_REQUESTS
+            || status.is_server_error()
+    }
+
+    /// Transport failures (connect, timeout, interrupted body) are worth another attempt
+    pub fn is_transient(error: &reqwest::Error) -> bool {
+        !error.is_builder() && !error.is_redirect() && !error.is_decode()
+    }
+}
+
+/// Parse `Retry-After` as delta seconds or an HTTP date
+pub fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let wait = date.signed_duration_since(chrono::Utc::now());
+    Some(wait.to_std().unwrap_or(Duration::ZERO))
+}
+
+/// Token bucket shared by all workers using one `EasyedaApi`
+#[derive(Debug)]
+pub struct RateLimiter {
+    /// Tokens added per second; `0` disables limiting
+    rate: f64,
+    burst: f64,
+    state: Mutex<Bucket>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    /// Negative when callers have reserved tokens that are not refilled yet
+    tokens: f64,
+    updated: Instant,
+}
+
+impl RateLimiter {
+    pub fn new(requests_per_second: f64, burst: u32) -> Self {
+        let burst = f64::from(burst.max(1));
+        Self {
+            rate: requests_per_second.max(0.0),
+            burst,
+            state: Mutex::new(Bucket {
+                tokens: burst,
+                updated: Instant::now(),
+            }),
+        }
+    }
+
+    fn refill(&self, bucket: &mut Bucket) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.rate).min(self.burst);
+        bucket.updated = now;
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.rate > 0.0
+    }
+
+    /// Wait until a request may be sent
+    pub async fn acquire(&self) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        // Reserve a token now and sleep off any deficit outside the lock, so
+        // waiting workers are served roughly in arrival order
+        let wait = {
+            let mut bucket = self.state.lock().await;
+            self.refill(&mut bucket);
+            bucket.tokens -= 1.0;
+            if bucket.tokens < 0.0 {
+                Duration::from_secs_f64(-bucket.tokens / self.rate)
+            } else {
+                Duration::ZERO
+            }
+        };
+
+        if !wait.is_zero() {
+            log::debug!("Rate limited, waiting {:?}", wait);
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Hold back every worker for `delay`, e.g. after a 429
+    pub async fn hold_off(&self, delay: Duration) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        let mut bucket = self.state.lock().await;
+        self.refill(&mut bucket);
+        bucket.tokens = bucket.tokens.min(-delay.as_secs_f64() * self.rate);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn backoff_is_jittered_and_capped() {
+        let policy = RetryPolicy {
+            max_attempts: 5,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(300),
+        };
+
+        for _ in 0..20 {
+            let first = policy.delay(1, None);
+            assert!(first >= Duration::from_millis(50) && first <= Duration::from_millis(100));
+            let capped = policy.delay(4, None);
+            assert!(capped >= Duration::from_millis(150) && capped <= Duration::from_millis(300));
+        }
+
+        assert_eq!(
+            policy.delay(1, Some(Duration::from_millis(200))),
+            Duration::from_millis(200)
+        );
+    }
+
+    #[test]
+    fn retry_after_is_capped_at_max_delay() {
+        let policy = RetryPolicy::default();
+        assert_eq!(
+            policy.delay(1, Some(Duration::from_secs(86400))),
+            policy.max_delay
+        );
+    }
+
+    #[test]
+    fn parses_retry_after_header() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after(&headers), None);
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("12"));
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(12)));
+
+        headers.insert(
+            RETRY_AFTER,
+            HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+        );
+        assert_eq!(retry_after(&headers), Some(Duration::ZERO));
+    }
+
+    #[tokio::test]
+    async fn rate_limiter_spaces_requests_after_burst() {
+        let limiter = RateLimiter::new(50.0, 2);
+        let start = Instant::now();
+        for _ in 0..4 {
+            limiter.acquire().await;
+        }
+        // Two tokens from the burst, then two more at 20 ms each
+        assert!(start.elapsed() >= Duration::from_millis(35));
+    }
+}
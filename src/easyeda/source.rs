@@ -0,0 +1,314 @@
+use crate::cli::Cli;
+use crate::easyeda::api::EasyedaApi;
+use crate::easyeda::cache::CachedSource;
+use crate::easyeda::models::{ApiResponse, ComponentData};
+use crate::error::{AppError, EasyedaError, Result};
+use async_trait::async_trait;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// Anything that can supply component metadata and 3D model payloads
+#[async_trait]
+pub trait ComponentSource: Send + Sync {
+    /// Raw EasyEDA component response for `lcsc_id`
+    async fn fetch_api_response(&self, lcsc_id: &str) -> Result<ApiResponse>;
+
+    async fn get_component_data(&self, lcsc_id: &str) -> Result<ComponentData> {
+        let api_response = self.fetch_api_response(lcsc_id).await?;
+        EasyedaApi::parse_component_data(lcsc_id, api_response)
+    }
+
+    async fn download_3d_obj(&self, uuid: &str) -> Result<Vec<u8>>;
+
+    /// Write the OBJ or STEP model for `uuid` to `dest`
+    async fn download_3d_to_file(&self, uuid: &str, model_type: &str, dest: &Path) -> Result<()>;
+}
+
+/// Lets one source, e.g. an `EasyedaApi` and its rate limiter, also be used outside the stack
+#[async_trait]
+impl<T: ComponentSource + ?Sized> ComponentSource for Arc<T> {
+    async fn fetch_api_response(&self, lcsc_id: &str) -> Result<ApiResponse> {
+        T::fetch_api_response(self, lcsc_id).await
+    }
+
+    async fn get_component_data(&self, lcsc_id: &str) -> Result<ComponentData> {
+        T::get_component_data(self, lcsc_id).await
+    }
+
+    async fn download_3d_obj(&self, uuid: &str) -> Result<Vec<u8>> {
+        T::download_3d_obj(self, uuid).await
+    }
+
+    async fn download_3d_to_file(&self, uuid: &str, model_type: &str, dest: &Path) -> Result<()> {
+        T::download_3d_to_file(self, uuid, model_type, dest).await
+    }
+}
+
+#[async_trait]
+impl ComponentSource for EasyedaApi {
+    async fn fetch_api_response(&self, lcsc_id: &str) -> Result<ApiResponse> {
+        EasyedaApi::fetch_api_response(self, lcsc_id).await
+    }
+
+    async fn download_3d_obj(&self, uuid: &str) -> Result<Vec<u8>> {
+        EasyedaApi::download_3d_obj(self, uuid).await
+    }
+
+    async fn download_3d_to_file(&self, uuid: &str, model_type: &str, dest: &Path) -> Result<()> {
+        EasyedaApi::download_3d_to_file(self, uuid, model_type, dest).await
+    }
+}
+
+/// Reads previously saved EasyEDA data from a directory:
+/// `<LCSC_ID>.json` (raw API response), `<uuid>.obj` and `<uuid>.step`
+pub struct LocalSource {
+    root: PathBuf,
+}
+
+impl LocalSource {
+    pub fn new(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+        }
+    }
+
+    pub fn component_path(&self, lcsc_id: &str) -> PathBuf {
+        self.root.join(format!("{}.json", lcsc_id))
+    }
+
+    pub fn model_path(&self, uuid: &str, model_type: &str) -> Result<PathBuf> {
+        let extension = match model_type {
+            "OBJ" => "obj",
+            "STEP" => "step",
+            _ => {
+                return Err(EasyedaError::InvalidData(format!(
+                    "Unknown model type: {}",
+                    model_type
+                ))
+                .into());
+            }
+        };
+        Ok(self.root.join(format!("{}.{}", uuid, extension)))
+    }
+}
+
+#[async_trait]
+impl ComponentSource for LocalSource {
+    async fn fetch_api_response(&self, lcsc_id: &str) -> Result<ApiResponse> {
+        let path = self.component_path(lcsc_id);
+        let content = tokio::fs::read_to_string(&path)
+            .await
+            .map_err(|_| EasyedaError::ComponentNotFound(lcsc_id.to_string()))?;
+
+        log::info!(
+            "Loading component data for {} from {}",
+            lcsc_id,
+            path.display()
+        );
+
+        Ok(serde_json::from_str(&content).map_err(EasyedaError::JsonParse)?)
+    }
+
+    async fn download_3d_obj(&self, uuid: &str) -> Result<Vec<u8>> {
+        let path = self.model_path(uuid, "OBJ")?;
+        tokio::fs::read(&path).await.map_err(|e| {
+            EasyedaError::InvalidData(format!("Failed to read {}: {}", path.display(), e)).into()
+        })
+    }
+
+    async fn download_3d_to_file(&self, uuid: &str, model_type: &str, dest: &Path) -> Result<()> {
+        let path = self.model_path(uuid, model_type)?;
+        tokio::fs::copy(&path, dest).await.map_err(|e| {
+            EasyedaError::InvalidData(format!("Failed to copy {}: {}", path.display(), e))
+        })?;
+        Ok(())
+    }
+}
+
+/// Tries `primary` first and falls back to `fallback` when it fails,
+/// e.g. a local cache in front of the live API
+pub struct ChainedSource {
+    primary: Box<dyn ComponentSource>,
+    fallback: Box<dyn ComponentSource>,
+}
+
+impl ChainedSource {
+    pub fn new(primary: Box<dyn ComponentSource>, fallback: Box<dyn ComponentSource>) -> Self {
+        Self { primary, fallback }
+    }
+}
+
+#[async_trait]
+impl ComponentSource for ChainedSource {
+    async fn fetch_api_response(&self, lcsc_id: &str) -> Result<ApiResponse> {
+        match self.primary.fetch_api_response(lcsc_id).await {
+            Ok(response) => Ok(response),
+            Err(e) => {
+                log::debug!("Primary source failed for {}: {}", lcsc_id, e);
+                self.fallback.fetch_api_response(lcsc_id).await
+            }
+        }
+    }
+
+    async fn download_3d_obj(&self, uuid: &str) -> Result<Vec<u8>> {
+        match self.primary.download_3d_obj(uuid).await {
+            Ok(data) => Ok(data),
+            Err(e) => {
+                log::debug!("Primary source failed for OBJ {}: {}", uuid, e);
+                self.fallback.download_3d_obj(uuid).await
+            }
+        }
+    }
+
+    async fn download_3d_to_file(&self, uuid: &str, model_type: &str, dest: &Path) -> Result<()> {
+        match self
+            .primary
+            .download_3d_to_file(uuid, model_type, dest)
+            .await
+        {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                log::debug!("Primary source failed for {} {}: {}", model_type, uuid, e);
+                self.fallback
+                    .download_3d_to_file(uuid, model_type, dest)
+                    .await
+            }
+        }
+    }
+}
+
+/// Build the source stack selected on the command line:
+/// `--source-dir` first, then `--cache-dir`, then the live API unless `--offline`
+pub fn source_from_cli(args: &Cli) -> Result<Box<dyn ComponentSource>> {
+    let api: Option<Box<dyn ComponentSource>> = if args.offline {
+        None
+    } else {
+        Some(Box::new(EasyedaApi::with_config(args.api_config()?)?))
+    };
+    source_with_upstream(args, api)
+}
+
+/// Like [`source_from_cli`], but on top of an already built `upstream` (`None` when offline)
+pub fn source_with_upstream(
+    args: &Cli,
+    upstream: Option<Box<dyn ComponentSource>>,
+) -> Result<Box<dyn ComponentSource>> {
+    let mut source = upstream;
+
+    if let Some(cache_dir) = &args.cache_dir {
+        source = Some(Box::new(CachedSource::new(
+            cache_dir,
+            Duration::from_secs(args.cache_ttl.saturating_mul(3600)),
+            args.refresh,
+            source,
+        )));
+    }
+
+    if let Some(source_dir) = &args.source_dir {
+        let local = Box::new(LocalSource::new(source_dir));
+        source = Some(match source {
+            Some(rest) => Box::new(ChainedSource::new(local, rest)),
+            None => local,
+        });
+    }
+
+    source.ok_or_else(|| {
+        AppError::Other("--offline requires --cache-dir or --source-dir".to_string())
+    })
+}
+
+/// Unique sibling of `path` to write to before renaming it into place, so
+/// concurrent writers of the same file never share a temp file
+pub(crate) fn temp_path(path: &Path) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(
+        ".{}.{}.part",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    path.with_file_name(name)
+}
+
+/// Fresh directory under the system temp dir for tests
+#[cfg(test)]
+pub(crate) fn temp_dir(name: &str) -> PathBuf {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    let dir = std::env::temp_dir().join(format!(
+        "nlbn-{}-{}-{}",
+        name,
+        std::process::id(),
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+    ));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RESPONSE: &str = r#"{
+        "success": true,
+        "result": {
+            "title": "NE555DR",
+            "description": "",
+            "dataStr": {
+                "head": {"x": 400, "y": 300, "c_para": {"package": "SOIC-8", "BOM_Manufacturer": "TI"}},
+                "shape": ["R~390~290~2~2~20~20~#880000~1~0~none~gge1~0~"]
+            },
+            "packageDetail": {
+                "dataStr": {"head": {"x": 4000, "y": 3000}, "shape": []}
+            }
+        }
+    }"#;
+
+    #[tokio::test]
+    async fn local_source_reads_saved_response_and_models() {
+        let root = temp_dir("local-source");
+        std::fs::write(root.join("C7593.json"), RESPONSE).unwrap();
+        std::fs::write(root.join("abc.step"), b"STEP").unwrap();
+
+        let source = LocalSource::new(&root);
+        let data = source.get_component_data("C7593").await.unwrap();
+        assert_eq!(data.title, "NE555DR");
+        assert_eq!(data.description, "TI NE555DR SOIC-8");
+        assert_eq!(data.bbox_x, 400.0);
+        assert_eq!(data.package_bbox_y, 3000.0);
+
+        let dest = root.join("out.step");
+        source
+            .download_3d_to_file("abc", "STEP", &dest)
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read(&dest).unwrap(), b"STEP");
+        assert!(source.download_3d_obj("abc").await.is_err());
+        assert!(source.get_component_data("C1").await.is_err());
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[tokio::test]
+    async fn chained_source_falls_back_when_primary_misses() {
+        let empty = temp_dir("chain-empty");
+        let saved = temp_dir("chain-saved");
+        std::fs::write(saved.join("C7593.json"), RESPONSE).unwrap();
+
+        let source = ChainedSource::new(
+            Box::new(LocalSource::new(&empty)),
+            Box::new(LocalSource::new(&saved)),
+        );
+        let data = source.get_component_data("C7593").await.unwrap();
+        assert_eq!(data.title, "NE555DR");
+
+        let _ = std::fs::remove_dir_all(empty);
+        let _ = std::fs::remove_dir_all(saved);
+    }
+}
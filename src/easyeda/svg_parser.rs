@@ -1,7 +1,11 @@
 use crate::error::{ConversionError, Result};
-use regex::Regex;
 
-#[derive(Debug, Clone)]
+/// One drawing command of an SVG path, with all coordinates absolute.
+///
+/// Relative commands are resolved against the current point, `H`/`V` become
+/// `LineTo`, and the smooth curves `S`/`T` become `CubicTo`/`QuadTo` with the
+/// reflected control point, so callers only handle these variants.
+#[derive(Debug, Clone, PartialEq)]
 pub enum SvgCommand {
     MoveTo {
         x: f64,
@@ -11,6 +15,20 @@ pub enum SvgCommand {
         x: f64,
         y: f64,
     },
+    CubicTo {
+        x1: f64,
+        y1: f64,
+        x2: f64,
+        y2: f64,
+        x: f64,
+        y: f64,
+    },
+    QuadTo {
+        x1: f64,
+        y1: f64,
+        x: f64,
+        y: f64,
+    },
     Arc {
         rx: f64,
         ry: f64,
@@ -23,68 +41,111 @@ pub enum SvgCommand {
     ClosePath,
 }
 
+/// Parse an SVG path (`d` attribute) into absolute commands
 pub fn parse_svg_path(path: &str) -> Result<Vec<SvgCommand>> {
+    let mut lexer = Lexer::new(path);
     let mut commands = Vec::new();
-    let path = path.trim();
-
-    // Regex patterns for SVG commands
-    let move_re = Regex::new(r"M\s*([-\d.]+)[,\s]+([-\d.]+)").unwrap();
-    let line_re = Regex::new(r"L\s*([-\d.]+)[,\s]+([-\d.]+)").unwrap();
-    let arc_re = Regex::new(
-        r"A\s*([-\d.]+)[,\s]+([-\d.]+)[,\s]+([-\d.]+)[,\s]+([01])[,\s]+([01])[,\s]+([-\d.]+)[,\s]+([-\d.]+)"
-    ).unwrap();
-
-    let mut pos = 0;
-    let chars: Vec<char> = path.chars().collect();
-
-    while pos < chars.len() {
-        let remaining = &path[pos..];
-
-        if remaining.starts_with('M') {
-            if let Some(cap) = move_re.captures(remaining) {
-                let x = cap[1].parse::<f64>().map_err(|_| {
-                    ConversionError::SvgParse("Invalid MoveTo X coordinate".to_string())
-                })?;
-                let y = cap[2].parse::<f64>().map_err(|_| {
-                    ConversionError::SvgParse("Invalid MoveTo Y coordinate".to_string())
-                })?;
+
+    let mut current = (0.0, 0.0);
+    let mut subpath_start = (0.0, 0.0);
+    // Second control point of the previous cubic / control point of the previous quadratic
+    let mut last_cubic_ctrl: Option<(f64, f64)> = None;
+    let mut last_quad_ctrl: Option<(f64, f64)> = None;
+
+    let mut command: Option<char> = None;
+
+    loop {
+        lexer.skip_separators();
+        let Some(c) = lexer.peek() else {
+            break;
+        };
+
+        if c.is_ascii_alphabetic() {
+            lexer.bump();
+            if !"MmLlHhVvCcSsQqTtAaZz".contains(c) {
+                return Err(svg_error(format!("unknown command '{}'", c)));
+            }
+            command = Some(c);
+        } else if command.is_none_or(|cmd| cmd == 'Z' || cmd == 'z') {
+            return Err(svg_error(format!(
+                "expected a command at offset {}",
+                lexer.pos
+            )));
+        }
+
+        let cmd = command.unwrap_or('M');
+        let relative = cmd.is_ascii_lowercase();
+        let base = if relative { current } else { (0.0, 0.0) };
+        let point = |lexer: &mut Lexer| -> Result<(f64, f64)> {
+            let x = lexer.number()?;
+            let y = lexer.number()?;
+            Ok((base.0 + x, base.1 + y))
+        };
+
+        let mut cubic_ctrl = None;
+        let mut quad_ctrl = None;
+
+        match cmd.to_ascii_uppercase() {
+            'M' => {
+                let (x, y) = point(&mut lexer)?;
                 commands.push(SvgCommand::MoveTo { x, y });
-                pos += cap.get(0).unwrap().len();
-            } else {
-                pos += 1;
-            }
-        } else if remaining.starts_with('L') {
-            if let Some(cap) = line_re.captures(remaining) {
-                let x = cap[1].parse::<f64>().map_err(|_| {
-                    ConversionError::SvgParse("Invalid LineTo X coordinate".to_string())
-                })?;
-                let y = cap[2].parse::<f64>().map_err(|_| {
-                    ConversionError::SvgParse("Invalid LineTo Y coordinate".to_string())
-                })?;
+                current = (x, y);
+                subpath_start = current;
+                // Further coordinate pairs are implicit line-tos
+                command = Some(if relative { 'l' } else { 'L' });
+            }
+            'L' => {
+                let (x, y) = point(&mut lexer)?;
                 commands.push(SvgCommand::LineTo { x, y });
-                pos += cap.get(0).unwrap().len();
-            } else {
-                pos += 1;
-            }
-        } else if remaining.starts_with('A') {
-            if let Some(cap) = arc_re.captures(remaining) {
-                let rx = cap[1]
-                    .parse::<f64>()
-                    .map_err(|_| ConversionError::SvgParse("Invalid Arc RX".to_string()))?;
-                let ry = cap[2]
-                    .parse::<f64>()
-                    .map_err(|_| ConversionError::SvgParse("Invalid Arc RY".to_string()))?;
-                let angle = cap[3]
-                    .parse::<f64>()
-                    .map_err(|_| ConversionError::SvgParse("Invalid Arc angle".to_string()))?;
-                let large_arc = &cap[4] == "1";
-                let sweep = &cap[5] == "1";
-                let x = cap[6].parse::<f64>().map_err(|_| {
-                    ConversionError::SvgParse("Invalid Arc X coordinate".to_string())
-                })?;
-                let y = cap[7].parse::<f64>().map_err(|_| {
-                    ConversionError::SvgParse("Invalid Arc Y coordinate".to_string())
-                })?;
+                current = (x, y);
+            }
+            'H' => {
+                let x = lexer.number()? + base.0;
+                commands.push(SvgCommand::LineTo { x, y: current.1 });
+                current.0 = x;
+            }
+            'V' => {
+                let y = lexer.number()? + base.1;
+                commands.push(SvgCommand::LineTo { x: current.0, y });
+                current.1 = y;
+            }
+            'C' | 'S' => {
+                let (x1, y1) = if cmd.eq_ignore_ascii_case(&'C') {
+                    point(&mut lexer)?
+                } else {
+                    reflect(last_cubic_ctrl, current)
+                };
+                let (x2, y2) = point(&mut lexer)?;
+                let (x, y) = point(&mut lexer)?;
+                commands.push(SvgCommand::CubicTo {
+                    x1,
+                    y1,
+                    x2,
+                    y2,
+                    x,
+                    y,
+                });
+                cubic_ctrl = Some((x2, y2));
+                current = (x, y);
+            }
+            'Q' | 'T' => {
+                let (x1, y1) = if cmd.eq_ignore_ascii_case(&'Q') {
+                    point(&mut lexer)?
+                } else {
+                    reflect(last_quad_ctrl, current)
+                };
+                let (x, y) = point(&mut lexer)?;
+                commands.push(SvgCommand::QuadTo { x1, y1, x, y });
+                quad_ctrl = Some((x1, y1));
+                current = (x, y);
+            }
+            'A' => {
+                let rx = lexer.number()?.abs();
+                let ry = lexer.number()?.abs();
+                let angle = lexer.number()?;
+                let large_arc = lexer.flag()?;
+                let sweep = lexer.flag()?;
+                let (x, y) = point(&mut lexer)?;
                 commands.push(SvgCommand::Arc {
                     rx,
                     ry,
@@ -94,21 +155,127 @@ pub fn parse_svg_path(path: &str) -> Result<Vec<SvgCommand>> {
                     x,
                     y,
                 });
-                pos += cap.get(0).unwrap().len();
-            } else {
-                pos += 1;
+                current = (x, y);
+            }
+            'Z' => {
+                commands.push(SvgCommand::ClosePath);
+                current = subpath_start;
             }
-        } else if remaining.starts_with('Z') || remaining.starts_with('z') {
-            commands.push(SvgCommand::ClosePath);
-            pos += 1;
-        } else {
-            pos += 1;
+            _ => unreachable!("command letters are validated above"),
         }
+
+        last_cubic_ctrl = cubic_ctrl;
+        last_quad_ctrl = quad_ctrl;
     }
 
     Ok(commands)
 }
 
+/// Control point mirrored through `current`, or `current` if the previous
+/// segment was not the same kind of curve
+fn reflect(ctrl: Option<(f64, f64)>, current: (f64, f64)) -> (f64, f64) {
+    match ctrl {
+        Some((x, y)) => (2.0 * current.0 - x, 2.0 * current.1 - y),
+        None => current,
+    }
+}
+
+fn svg_error(message: String) -> crate::error::AppError {
+    ConversionError::SvgParse(message).into()
+}
+
+struct Lexer<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(path: &'a str) -> Self {
+        Self {
+            bytes: path.as_bytes(),
+            pos: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.bytes.get(self.pos).map(|&b| b as char)
+    }
+
+    fn bump(&mut self) {
+        self.pos += 1;
+    }
+
+    fn skip_separators(&mut self) {
+        while let Some(c) = self.peek() {
+            if c.is_ascii_whitespace() || c == ',' {
+                self.bump();
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Read a number: `-1`, `.5`, `1e-3`; "1.5.5" is two numbers and "1-2" is two numbers
+    fn number(&mut self) -> Result<f64> {
+        self.skip_separators();
+        let start = self.pos;
+
+        if matches!(self.peek(), Some('+' | '-')) {
+            self.bump();
+        }
+        let mut digits = self.digits();
+        if self.peek() == Some('.') {
+            self.bump();
+            digits += self.digits();
+        }
+        if digits == 0 {
+            return Err(svg_error(format!("expected a number at offset {}", start)));
+        }
+        if matches!(self.peek(), Some('e' | 'E')) {
+            let mark = self.pos;
+            self.bump();
+            if matches!(self.peek(), Some('+' | '-')) {
+                self.bump();
+            }
+            if self.digits() == 0 {
+                // Not an exponent after all
+                self.pos = mark;
+            }
+        }
+
+        let text = std::str::from_utf8(&self.bytes[start..self.pos]).unwrap_or_default();
+        text.parse::<f64>()
+            .map_err(|_| svg_error(format!("invalid number '{}'", text)))
+    }
+
+    fn digits(&mut self) -> usize {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.bump();
+        }
+        self.pos - start
+    }
+
+    /// Arc flags are a single `0` or `1` and may be written without separators
+    fn flag(&mut self) -> Result<bool> {
+        self.skip_separators();
+        match self.peek() {
+            Some('0') => {
+                self.bump();
+                Ok(false)
+            }
+            Some('1') => {
+                self.bump();
+                Ok(true)
+            }
+            _ => Err(svg_error(format!(
+                "expected arc flag at offset {}",
+                self.pos
+            ))),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -133,4 +300,73 @@ mod tests {
         let commands = parse_svg_path(path).unwrap();
         assert_eq!(commands.len(), 2);
     }
+
+    #[test]
+    fn test_relative_and_implicit_commands() {
+        let commands = parse_svg_path("m10 10 5 0h5v-5l-1-1.5.5 0zM0 0").unwrap();
+        assert_eq!(
+            commands,
+            vec![
+                SvgCommand::MoveTo { x: 10.0, y: 10.0 },
+                SvgCommand::LineTo { x: 15.0, y: 10.0 },
+                SvgCommand::LineTo { x: 20.0, y: 10.0 },
+                SvgCommand::LineTo { x: 20.0, y: 5.0 },
+                SvgCommand::LineTo { x: 19.0, y: 3.5 },
+                SvgCommand::LineTo { x: 19.5, y: 3.5 },
+                SvgCommand::ClosePath,
+                SvgCommand::MoveTo { x: 0.0, y: 0.0 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_curves_and_smooth_reflection() {
+        let commands =
+            parse_svg_path("M0,0 C0,10 10,10 10,0 s10-10 10,0 Q25,5 30,0 t10,0").unwrap();
+        assert_eq!(
+            commands[2],
+            SvgCommand::CubicTo {
+                x1: 10.0,
+                y1: -10.0,
+                x2: 20.0,
+                y2: -10.0,
+                x: 20.0,
+                y: 0.0,
+            }
+        );
+        assert_eq!(
+            commands[4],
+            SvgCommand::QuadTo {
+                x1: 35.0,
+                y1: -5.0,
+                x: 40.0,
+                y: 0.0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_compact_arc_flags_and_exponents() {
+        let commands = parse_svg_path("M0 0a5 5 0 1010 0L1e1 2E-1").unwrap();
+        assert_eq!(
+            commands[1],
+            SvgCommand::Arc {
+                rx: 5.0,
+                ry: 5.0,
+                angle: 0.0,
+                large_arc: true,
+                sweep: false,
+                x: 10.0,
+                y: 0.0,
+            }
+        );
+        assert_eq!(commands[2], SvgCommand::LineTo { x: 10.0, y: 0.2 });
+    }
+
+    #[test]
+    fn test_rejects_malformed_paths() {
+        assert!(parse_svg_path("M 10").is_err());
+        assert!(parse_svg_path("10 10").is_err());
+        assert!(parse_svg_path("M0 0 X 1 1").is_err());
+    }
 }
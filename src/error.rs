@@ -16,6 +16,9 @@ pub enum EasyedaError {
 
     #[error("Invalid component data: {0}")]
     InvalidData(String),
+
+    #[error("Invalid API configuration: {0}")]
+    Config(String),
 }
 
 #[derive(Error, Debug)]
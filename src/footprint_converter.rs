@@ -1,7 +1,10 @@
 use crate::cli::Cli;
-use crate::converter::{Converter, angle_to_ki, sanitize_name};
+use crate::converter::{
+    Converter, CurveOptions, angle_to_ki, flatten_cubic, flatten_quad, quad_to_cubic, sanitize_name,
+};
+use crate::courtyard::add_courtyard;
 use crate::easyeda::svg_parser::{SvgCommand, parse_svg_path};
-use crate::easyeda::{ComponentData, FootprintImporter};
+use crate::easyeda::{ComponentData, EeFootprint, EeFootprintArc, EeSolidRegion, import_footprint};
 use crate::error::Result;
 use crate::kicad;
 use crate::library::{FileWriteStatus, LibraryManager};
@@ -12,8 +15,9 @@ pub fn convert_footprint(
     lib_manager: &LibraryManager,
     lcsc_id: &str,
 ) -> Result<()> {
-    let ee_footprint = FootprintImporter::parse(&component_data.package_detail)?;
+    let ee_footprint = import_footprint(&component_data.package_detail)?;
     let converter = Converter::new(args.kicad_version());
+    let curves = args.curve_options();
 
     // Use LCSC ID as unique identifier to prevent name collisions
     let footprint_name = format!("{}_{}", sanitize_name(&component_data.title), lcsc_id);
@@ -21,13 +25,13 @@ pub fn convert_footprint(
     // Convert EasyEDA footprint to KiCad footprint
     let mut ki_footprint = kicad::KiFootprint {
         name: footprint_name,
-        pads: Vec::new(),
-        tracks: Vec::new(),
-        circles: Vec::new(),
-        arcs: Vec::new(),
-        texts: Vec::new(),
-        lines: Vec::new(),
-        model_3d: None,
+        description: component_data.description.trim().to_string(),
+        tags: component_data
+            .c_para
+            .get("package")
+            .map(|package| package.trim().to_string())
+            .unwrap_or_default(),
+        ..Default::default()
     };
 
     // Convert pads with bbox adjustment
@@ -86,62 +90,74 @@ pub fn convert_footprint(
         // Apply bbox normalization for footprint coordinates
         let adjusted_x = ee_pad.x - component_data.package_bbox_x;
         let adjusted_y = ee_pad.y - component_data.package_bbox_y;
-        let adjusted_x_mm = converter.px_to_mm(adjusted_x);
-        let adjusted_y_mm = converter.px_to_mm(adjusted_y);
 
-        // Handle polygon pads
-        let (size_x, size_y, rotation, polygon) =
-            if ee_pad.shape == "POLYGON" && !ee_pad.points.is_empty() {
-                // Parse points: space-separated x y coordinates
-                let coords: Vec<f64> = ee_pad
-                    .points
-                    .split_whitespace()
-                    .filter_map(|s| s.parse::<f64>().ok())
-                    .collect();
-
-                if coords.len() >= 4 {
-                    // At least 2 points (x,y pairs)
-                    // Convert coordinates to mm and make relative to pad position
-                    let mut poly_str =
-                        String::from("\n\t\t(primitives \n\t\t\t(gr_poly \n\t\t\t\t(pts");
-
-                    for i in (0..coords.len()).step_by(2) {
-                        if i + 1 < coords.len() {
-                            let abs_x_mm =
-                                converter.px_to_mm(coords[i] - component_data.package_bbox_x);
-                            let abs_y_mm =
-                                converter.px_to_mm(coords[i + 1] - component_data.package_bbox_y);
-                            let rel_x = abs_x_mm - adjusted_x_mm;
-                            let rel_y = abs_y_mm - adjusted_y_mm;
-                            poly_str.push_str(&format!(" (xy {:.2} {:.2})", rel_x, rel_y));
-                        }
-                    }
+        let mut shape = kicad::PadShape::from_easyeda(&ee_pad.shape, ee_pad.corner_radius);
+        let mut pos = (adjusted_x, adjusted_y);
+        let mut size = (ee_pad.width.max(0.01), ee_pad.height.max(0.01));
+        let mut rotation = angle_to_ki(ee_pad.rotation);
+        let mut polygon = None;
+        let mut chamfer = None;
 
-                    poly_str.push_str("\n\t\t\t\t) \n\t\t\t\t(width 0.1) \n\t\t\t)\n\t\t)\n\t");
+        // Handle polygon pads
+        if ee_pad.shape == "POLYGON" && !ee_pad.points.is_empty() {
+            // Parse points: space-separated x y coordinates
+            let points: Vec<(f64, f64)> = ee_pad
+                .points
+                .split_whitespace()
+                .filter_map(|s| s.parse::<f64>().ok())
+                .collect::<Vec<_>>()
+                .chunks_exact(2)
+                .map(|xy| (xy[0], xy[1]))
+                .collect();
 
-                    // Set minimal pad size (enforced minimum 0.01) and force orientation to 0
-                    (0.01, 0.01, 0.0, Some(poly_str))
+            if let Some(rect) = chamfered_rect(&points) {
+                // Rectangles with 45° cut corners are native KiCad pads
+                shape = if rect.chamfer.is_some() {
+                    kicad::PadShape::RoundRect
                 } else {
-                    let rot = angle_to_ki(ee_pad.rotation);
-                    (ee_pad.width.max(0.01), ee_pad.height.max(0.01), rot, None)
-                }
-            } else {
-                let rot = angle_to_ki(ee_pad.rotation);
-                (ee_pad.width.max(0.01), ee_pad.height.max(0.01), rot, None)
-            };
+                    kicad::PadShape::Rect
+                };
+                pos = (
+                    rect.center.0 - component_data.package_bbox_x,
+                    rect.center.1 - component_data.package_bbox_y,
+                );
+                size = rect.size;
+                rotation = 0.0;
+                chamfer = rect.chamfer;
+            } else if points.len() >= 2 {
+                // Outline relative to the pad position, with minimal pad size
+                // (enforced minimum 0.01) and orientation forced to 0
+                polygon = Some(
+                    points
+                        .iter()
+                        .map(|&(x, y)| (x - ee_pad.x, y - ee_pad.y))
+                        .collect(),
+                );
+                size = (0.01, 0.01);
+                rotation = 0.0;
+            }
+        }
+
+        let corner_ratio = if shape == kicad::PadShape::RoundRect {
+            (ee_pad.corner_radius / size.0.min(size.1)).clamp(0.0, 0.5)
+        } else {
+            0.0
+        };
 
         ki_footprint.pads.push(kicad::KiPad {
             number: ee_pad.number.clone(),
             pad_type,
-            shape: kicad::PadShape::from_easyeda(&ee_pad.shape),
-            pos_x: adjusted_x,
-            pos_y: adjusted_y,
-            size_x,
-            size_y,
+            shape,
+            pos_x: pos.0,
+            pos_y: pos.1,
+            size_x: size.0,
+            size_y: size.1,
             rotation,
             layers,
             drill,
             polygon,
+            corner_ratio,
+            chamfer,
         });
     }
 
@@ -223,6 +239,8 @@ pub fn convert_footprint(
                 offset_y: 0.0,
             }),
             polygon: None,
+            corner_ratio: 0.0,
+            chamfer: None,
         });
     }
 
@@ -252,6 +270,8 @@ pub fn convert_footprint(
                 offset_y: 0.0,
             }),
             polygon: None,
+            corner_ratio: 0.0,
+            chamfer: None,
         });
     }
 
@@ -269,6 +289,12 @@ pub fn convert_footprint(
             }
         };
 
+        let adjust = |(x, y): (f64, f64)| {
+            (
+                x - component_data.package_bbox_x,
+                y - component_data.package_bbox_y,
+            )
+        };
         let mut current_pos = (0.0_f64, 0.0_f64);
         for cmd in &commands {
             match cmd {
@@ -335,6 +361,24 @@ pub fn convert_footprint(
 
                     current_pos = (end_x, end_y);
                 }
+                SvgCommand::CubicTo {
+                    x1,
+                    y1,
+                    x2,
+                    y2,
+                    x,
+                    y,
+                } => {
+                    let curve = [current_pos, (*x1, *y1), (*x2, *y2), (*x, *y)];
+                    push_curve(&mut ki_footprint, curve, adjust, ee_arc, curves);
+                    current_pos = (*x, *y);
+                }
+                SvgCommand::QuadTo { x1, y1, x, y } => {
+                    let (c1, c2) = quad_to_cubic(current_pos, (*x1, *y1), (*x, *y));
+                    let curve = [current_pos, c1, c2, (*x, *y)];
+                    push_curve(&mut ki_footprint, curve, adjust, ee_arc, curves);
+                    current_pos = (*x, *y);
+                }
                 SvgCommand::LineTo { x, y } => {
                     current_pos = (*x, *y);
                 }
@@ -410,37 +454,89 @@ pub fn convert_footprint(
         });
     }
 
-    // Add 3D model reference if available
-    if let Some(model_info) = &component_data.model_3d {
-        if args.model_3d || args.full {
-            // Use LCSC ID as unique identifier to prevent name collisions
-            let model_name = format!("{}_{}", sanitize_name(&model_info.title), lcsc_id);
-
-            // Default to project-relative paths (KIPRJMOD) for easier setup
-            // Use --project-relative flag to force global paths if needed
-            // Prefer STEP format as it's more widely supported
-            let model_lib_name = lib_manager.model_lib_name();
-            let model_dir_name = lib_manager.model_dir_name();
-            let model_path = if args.project_relative {
-                format!("${{KIPRJMOD}}/{}/{}.step", model_dir_name, model_name)
-            } else {
-                format!(
-                    "${{{}}}/{}/{}.step",
-                    model_lib_name, model_dir_name, model_name
-                )
-            };
-
-            ki_footprint.model_3d = Some(kicad::Ki3dModel {
-                path: model_path,
-                offset: (0.0, 0.0, 0.0),
-                scale: (1.0, 1.0, 1.0),
-                rotate: (0.0, 0.0, 0.0),
-            });
+    // Convert solid regions to filled areas, board cutouts and keep-outs
+    for ee_region in &ee_footprint.solid_regions {
+        let outlines = match path_outlines(&ee_region.path, &converter, curves.tolerance) {
+            Ok(outlines) => outlines,
+            Err(e) => {
+                log::warn!(
+                    "Skipping solid region with invalid SVG path: {} ({})",
+                    ee_region.path,
+                    e
+                );
+                continue;
+            }
+        };
+        // Regions are filled, so open outlines are closed as well
+        for outline in outlines.into_iter().filter(|o| o.points.len() >= 3) {
+            let points = outline
+                .points
+                .into_iter()
+                .map(|(x, y)| {
+                    (
+                        x - component_data.package_bbox_x,
+                        y - component_data.package_bbox_y,
+                    )
+                })
+                .collect();
+            push_region(&mut ki_footprint, ee_region, points, &converter);
         }
     }
 
+    // Add 3D model reference if available
+    if let Some(model_info) = &component_data.model_3d
+        && (args.model_3d || args.full)
+    {
+        // Use LCSC ID as unique identifier to prevent name collisions
+        let model_name = format!("{}_{}", sanitize_name(&model_info.title), lcsc_id);
+
+        // Default to project-relative paths (KIPRJMOD) for easier setup
+        // Use --project-relative flag to force global paths if needed
+        // Prefer STEP format as it's more widely supported
+        let model_lib_name = lib_manager.model_lib_name();
+        let model_dir_name = lib_manager.model_dir_name();
+        let model_path = if args.project_relative {
+            format!("${{KIPRJMOD}}/{}/{}.step", model_dir_name, model_name)
+        } else {
+            format!(
+                "${{{}}}/{}/{}.step",
+                model_lib_name, model_dir_name, model_name
+            )
+        };
+
+        let (offset, rotate) = model_transform(model_info, component_data, &converter);
+        ki_footprint.model_3d = Some(kicad::Ki3dModel {
+            path: model_path,
+            offset,
+            scale: (1.0, 1.0, 1.0),
+            rotate,
+        });
+    }
+
+    // SVG nodes go on F.Fab after the courtyard is placed, unless the
+    // courtyard should enclose them too
+    if args.courtyard_from_outline {
+        push_svg_nodes(
+            &mut ki_footprint,
+            &ee_footprint,
+            component_data,
+            &converter,
+            curves,
+        );
+        add_courtyard(&mut ki_footprint, args.courtyard, &converter);
+    } else {
+        add_courtyard(&mut ki_footprint, args.courtyard, &converter);
+        push_svg_nodes(
+            &mut ki_footprint,
+            &ee_footprint,
+            component_data,
+            &converter,
+            curves,
+        );
+    }
+
     // Export footprint
-    let exporter = kicad::FootprintExporter::new();
+    let exporter = kicad::FootprintExporter::new(args.kicad_version());
     let footprint_data = exporter.export(&ki_footprint)?;
     let (_, status) = lib_manager.write_footprint_if_needed(
         &ki_footprint.name,
@@ -459,3 +555,544 @@ pub fn convert_footprint(
 
     Ok(())
 }
+
+/// Add a cubic Bezier (in EasyEDA coordinates) as an `fp_curve`, or as
+/// line segments within the flattening tolerance
+fn push_curve(
+    footprint: &mut kicad::KiFootprint,
+    [p0, p1, p2, p3]: [(f64, f64); 4],
+    adjust: impl Fn((f64, f64)) -> (f64, f64),
+    ee_arc: &EeFootprintArc,
+    curves: CurveOptions,
+) {
+    let layer = kicad::map_layer(ee_arc.layer_id);
+
+    if curves.native {
+        footprint.curves.push(kicad::KiCurve {
+            points: [adjust(p0), adjust(p1), adjust(p2), adjust(p3)],
+            width: ee_arc.stroke_width,
+            layer,
+        });
+        return;
+    }
+
+    let mut start = adjust(p0);
+    for point in flatten_cubic(p0, p1, p2, p3, curves.tolerance) {
+        let end = adjust(point);
+        footprint.lines.push(kicad::KiLine {
+            start_x: start.0,
+            start_y: start.1,
+            end_x: end.0,
+            end_y: end.1,
+            width: ee_arc.stroke_width,
+            layer: layer.clone(),
+        });
+        start = end;
+    }
+}
+
+/// Fabrication layer line width in mm, as in KiCad's own libraries
+const FAB_LINE_WIDTH: f64 = 0.1;
+
+/// Draw the `outline3D` SVG node, the outline of the 3D model's body, on
+/// `F.Fab`: closed outlines as `fp_poly`, open ones as lines. Other SVG nodes
+/// are not body outlines and are skipped.
+fn push_svg_nodes(
+    footprint: &mut kicad::KiFootprint,
+    ee_footprint: &EeFootprint,
+    component_data: &ComponentData,
+    converter: &Converter,
+    curves: CurveOptions,
+) {
+    let adjust = |(x, y): (f64, f64)| {
+        (
+            x - component_data.package_bbox_x,
+            y - component_data.package_bbox_y,
+        )
+    };
+    let width = converter.mm_to_px(FAB_LINE_WIDTH);
+
+    for node in ee_footprint
+        .svg_nodes
+        .iter()
+        .filter(|node| node.node_type == "outline3D")
+    {
+        let outlines = match path_outlines(&node.path, converter, curves.tolerance) {
+            Ok(outlines) => outlines,
+            Err(e) => {
+                log::warn!("Skipping SVG node with invalid path: {} ({})", node.path, e);
+                continue;
+            }
+        };
+
+        for outline in outlines {
+            let points: Vec<(f64, f64)> = outline.points.into_iter().map(adjust).collect();
+            if outline.closed {
+                footprint.polygons.push(kicad::KiPolygon {
+                    points,
+                    width,
+                    layer: "F.Fab".to_string(),
+                    fill: false,
+                });
+                continue;
+            }
+            for segment in points.windows(2) {
+                footprint.lines.push(kicad::KiLine {
+                    start_x: segment[0].0,
+                    start_y: segment[0].1,
+                    end_x: segment[1].0,
+                    end_y: segment[1].1,
+                    width,
+                    layer: "F.Fab".to_string(),
+                });
+            }
+        }
+    }
+}
+
+/// One subpath of an SVG path, flattened to points in EasyEDA coordinates
+struct PathOutline {
+    points: Vec<(f64, f64)>,
+    /// Ended with `Z` or back at its start; the start point is not repeated
+    closed: bool,
+}
+
+/// Subpaths of an SVG path with arcs and curves flattened; closed ones have
+/// at least three points, open ones at least two
+fn path_outlines(path: &str, converter: &Converter, tolerance: f64) -> Result<Vec<PathOutline>> {
+    let mut outlines = Vec::new();
+    let mut outline = PathOutline {
+        points: Vec::new(),
+        closed: false,
+    };
+    let mut current = (0.0, 0.0);
+
+    for cmd in parse_svg_path(path)? {
+        let points = &mut outline.points;
+        match cmd {
+            SvgCommand::MoveTo { x, y } => {
+                let next = PathOutline {
+                    points: vec![(x, y)],
+                    closed: false,
+                };
+                outlines.push(std::mem::replace(&mut outline, next));
+            }
+            SvgCommand::LineTo { x, y } => points.push((x, y)),
+            SvgCommand::CubicTo {
+                x1,
+                y1,
+                x2,
+                y2,
+                x,
+                y,
+            } => points.extend(flatten_cubic(
+                current,
+                (x1, y1),
+                (x2, y2),
+                (x, y),
+                tolerance,
+            )),
+            SvgCommand::QuadTo { x1, y1, x, y } => {
+                points.extend(flatten_quad(current, (x1, y1), (x, y), tolerance))
+            }
+            SvgCommand::Arc {
+                rx,
+                ry,
+                angle,
+                large_arc,
+                sweep,
+                x,
+                y,
+            } => match converter.svg_arc(current, (x, y), (rx, ry), angle, large_arc, sweep) {
+                Ok(arc) => points.extend(arc.flatten(tolerance)),
+                Err(_) => points.push((x, y)),
+            },
+            SvgCommand::ClosePath => {
+                outline.closed = true;
+                current = outline.points.first().copied().unwrap_or(current);
+                continue;
+            }
+        }
+        if let Some(&last) = outline.points.last() {
+            current = last;
+        }
+    }
+    outlines.push(outline);
+
+    for outline in &mut outlines {
+        let points = &mut outline.points;
+        if points.len() > 2 && points.first() == points.last() {
+            points.pop();
+            outline.closed = true;
+        }
+    }
+    outlines.retain(|outline| outline.points.len() >= if outline.closed { 3 } else { 2 });
+    Ok(outlines)
+}
+
+/// Add one outline of a solid region (relative to the footprint origin).
+///
+/// `npth` regions and cutouts on the board outline or multi-layer are board
+/// cutouts on `Edge.Cuts`; other cutouts keep copper pours out of their
+/// copper layer. Solid regions become filled polygons, on both copper layers
+/// for the multi-layer.
+fn push_region(
+    footprint: &mut kicad::KiFootprint,
+    region: &EeSolidRegion,
+    points: Vec<(f64, f64)>,
+    converter: &Converter,
+) {
+    let copper_layers = match region.layer_id {
+        1 | 2 => vec![kicad::map_layer(region.layer_id)],
+        11 => vec!["F.Cu".to_string(), "B.Cu".to_string()],
+        _ => Vec::new(),
+    };
+    let board_cutout = region.region_type == "npth"
+        || (region.region_type == "cutout" && copper_layers.len() != 1);
+
+    if board_cutout {
+        footprint.polygons.push(kicad::KiPolygon {
+            points,
+            width: converter.mm_to_px(0.05),
+            layer: "Edge.Cuts".to_string(),
+            fill: false,
+        });
+    } else if region.region_type == "cutout" {
+        footprint.zones.push(kicad::KiZone {
+            points,
+            layers: copper_layers,
+            keepout: kicad::Keepout {
+                copperpour: true,
+                ..Default::default()
+            },
+        });
+    } else {
+        let layers = if copper_layers.is_empty() {
+            vec![kicad::map_layer(region.layer_id)]
+        } else {
+            copper_layers
+        };
+        for layer in layers {
+            footprint.polygons.push(kicad::KiPolygon {
+                points: points.clone(),
+                width: 0.0,
+                layer,
+                fill: true,
+            });
+        }
+    }
+}
+
+/// A polygon pad outline that is an axis-aligned rectangle, possibly with
+/// some corners cut off at 45°
+struct ChamferedRect {
+    center: (f64, f64),
+    size: (f64, f64),
+    chamfer: Option<kicad::PadChamfer>,
+}
+
+/// Recognise a polygon pad that KiCad can draw as a `rect` or chamfered
+/// `roundrect` pad: every vertex lies on the bounding box, and each corner is
+/// either a vertex or cut off by the same amount along both edges
+fn chamfered_rect(points: &[(f64, f64)]) -> Option<ChamferedRect> {
+    const EPS: f64 = 1e-3;
+    let near = |a: f64, b: f64| (a - b).abs() < EPS;
+
+    let mut points = points.to_vec();
+    points.dedup_by(|a, b| near(a.0, b.0) && near(a.1, b.1));
+    if let (Some(first), Some(last)) = (points.first(), points.last())
+        && points.len() > 1
+        && near(first.0, last.0)
+        && near(first.1, last.1)
+    {
+        points.pop();
+    }
+    if points.len() < 4 {
+        return None;
+    }
+
+    let (min_x, max_x, min_y, max_y) = points.iter().fold(
+        (f64::MAX, f64::MIN, f64::MAX, f64::MIN),
+        |(min_x, max_x, min_y, max_y), &(x, y)| {
+            (min_x.min(x), max_x.max(x), min_y.min(y), max_y.max(y))
+        },
+    );
+    let (width, height) = (max_x - min_x, max_y - min_y);
+    if width < EPS || height < EPS {
+        return None;
+    }
+    let on_edge =
+        |&(x, y): &(f64, f64)| near(x, min_x) || near(x, max_x) || near(y, min_y) || near(y, max_y);
+    if !points.iter().all(on_edge) {
+        return None;
+    }
+
+    // Cut size of the corner at (cx, cy), 0 if the corner is a vertex
+    let cut = |cx: f64, cy: f64| -> Option<f64> {
+        if points.iter().any(|&(x, y)| near(x, cx) && near(y, cy)) {
+            return Some(0.0);
+        }
+        let along_x = points
+            .iter()
+            .filter(|&&(_, y)| near(y, cy))
+            .map(|&(x, _)| (x - cx).abs())
+            .fold(f64::MAX, f64::min);
+        let along_y = points
+            .iter()
+            .filter(|&&(x, _)| near(x, cx))
+            .map(|&(_, y)| (y - cy).abs())
+            .fold(f64::MAX, f64::min);
+        near(along_x, along_y).then_some(along_x)
+    };
+    // Y grows downwards, so min_y is the top edge
+    let cuts = [
+        cut(min_x, min_y)?,
+        cut(max_x, min_y)?,
+        cut(min_x, max_y)?,
+        cut(max_x, max_y)?,
+    ];
+
+    let chamfered: Vec<f64> = cuts.iter().copied().filter(|&c| c > 0.0).collect();
+    // Any vertex not accounted for by a corner would be a notch in an edge
+    if points.len() != 4 + chamfered.len() {
+        return None;
+    }
+
+    let chamfer = match chamfered.first() {
+        None => None,
+        Some(&size) => {
+            let ratio = size / width.min(height);
+            if chamfered.iter().any(|&c| !near(c, size)) || ratio > 0.5 + EPS {
+                return None;
+            }
+            Some(kicad::PadChamfer {
+                ratio: ratio.min(0.5),
+                top_left: cuts[0] > 0.0,
+                top_right: cuts[1] > 0.0,
+                bottom_left: cuts[2] > 0.0,
+                bottom_right: cuts[3] > 0.0,
+            })
+        }
+    };
+
+    Some(ChamferedRect {
+        center: ((min_x + max_x) / 2.0, (min_y + max_y) / 2.0),
+        size: (width, height),
+        chamfer,
+    })
+}
+
+/// KiCad model offset (mm) and rotation (degrees) from the `outline3D` placement.
+///
+/// The offset is relative to the footprint origin (the package bbox) with Y
+/// pointing up, as KiCad expects; EasyEDA rotates clockwise, KiCad counter-clockwise.
+fn model_transform(
+    model_info: &crate::easyeda::Model3dInfo,
+    component_data: &ComponentData,
+    converter: &Converter,
+) -> ((f64, f64, f64), (f64, f64, f64)) {
+    let (x, y) = model_info.origin.map_or((0.0, 0.0), |(x, y)| {
+        (
+            converter.px_to_mm(x - component_data.package_bbox_x),
+            converter.px_to_mm(component_data.package_bbox_y - y),
+        )
+    });
+    let z = converter.px_to_mm(model_info.z);
+
+    let ki_angle = |angle: f64| (360.0 - angle).rem_euclid(360.0);
+    let (rx, ry, rz) = model_info.rotation;
+
+    ((x, y, z), (ki_angle(rx), ki_angle(ry), ki_angle(rz)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::KicadVersion;
+    use crate::easyeda::Model3dInfo;
+
+    /// Component whose package origin is (4000, 3000) px
+    fn component_data() -> ComponentData {
+        serde_json::from_value(serde_json::json!({
+            "lcsc_id": "C2040", "title": "RP2040", "description": "",
+            "data_str": [], "bbox_x": 0.0, "bbox_y": 0.0,
+            "package_detail": [], "package_bbox_x": 4000.0, "package_bbox_y": 3000.0,
+            "model_3d": null, "manufacturer": "", "datasheet": "", "jlc_id": ""
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn model_transform_is_relative_to_package_origin() {
+        let component_data = component_data();
+        let model_info = Model3dInfo {
+            uuid: "abc".to_string(),
+            title: "QFN-56".to_string(),
+            origin: Some((4010.0, 2995.0)),
+            z: 2.0,
+            rotation: (0.0, 0.0, 90.0),
+        };
+
+        let (offset, rotate) = model_transform(
+            &model_info,
+            &component_data,
+            &Converter::new(KicadVersion::V6),
+        );
+
+        assert!((offset.0 - 2.54).abs() < 1e-9);
+        assert!((offset.1 - 1.27).abs() < 1e-9);
+        assert!((offset.2 - 0.508).abs() < 1e-9);
+        assert_eq!(rotate, (0.0, 0.0, 270.0));
+    }
+
+    #[test]
+    fn maps_solid_region_types() {
+        let converter = Converter::new(KicadVersion::V8);
+        let square = || vec![(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)];
+        let region = |layer_id: i32, region_type: &str| EeSolidRegion {
+            layer_id,
+            path: String::new(),
+            region_type: region_type.to_string(),
+        };
+        let mut footprint = kicad::KiFootprint {
+            name: "QFN".to_string(),
+            ..Default::default()
+        };
+
+        push_region(&mut footprint, &region(1, "solid"), square(), &converter);
+        push_region(&mut footprint, &region(11, "npth"), square(), &converter);
+        push_region(&mut footprint, &region(11, "cutout"), square(), &converter);
+        push_region(&mut footprint, &region(2, "cutout"), square(), &converter);
+
+        let polygons: Vec<(&str, bool)> = footprint
+            .polygons
+            .iter()
+            .map(|p| (p.layer.as_str(), p.fill))
+            .collect();
+        assert_eq!(
+            polygons,
+            [("F.Cu", true), ("Edge.Cuts", false), ("Edge.Cuts", false)]
+        );
+        assert_eq!(footprint.zones.len(), 1);
+        assert_eq!(footprint.zones[0].layers, ["B.Cu"]);
+        assert!(footprint.zones[0].keepout.copperpour && !footprint.zones[0].keepout.tracks);
+    }
+
+    #[test]
+    fn flattens_region_outlines() {
+        let converter = Converter::new(KicadVersion::V8);
+        let outlines = path_outlines(
+            "M 0 0 L 10 0 L 10 10 Z M 20 0 A 5 5 0 0 1 30 0 Z M 0 20 L 5 20",
+            &converter,
+            0.01,
+        )
+        .unwrap();
+
+        assert_eq!(outlines.len(), 3);
+        assert_eq!(outlines[0].points, [(0.0, 0.0), (10.0, 0.0), (10.0, 10.0)]);
+        assert!(outlines[0].closed && outlines[1].closed && !outlines[2].closed);
+        // Clockwise on screen (Y pointing down): the half circle through (25, -5)
+        let arc = &outlines[1].points;
+        assert!(arc.len() > 10);
+        assert!(
+            arc.iter()
+                .all(|&(x, y)| ((x - 25.0).hypot(y) - 5.0).abs() < 1e-9)
+        );
+        assert!(arc.iter().any(|&(_, y)| (y + 5.0).abs() < 0.05));
+    }
+
+    #[test]
+    fn draws_svg_nodes_on_fab() {
+        let component_data = component_data();
+        let ee_footprint = import_footprint(&[
+            r#"SVGNODE~{"nodeName":"g","layerid":"19","attrs":{"c_etype":"outline3D"},"childNodes":[{"nodeName":"polygon","attrs":{"points":"3990,2990 4010,2990 4010,3010 3990,3010"}},{"nodeName":"polyline","attrs":{"points":"3995 3000 4000 3000 4005 3005"}}]}"#
+                .to_string(),
+            // Silkscreen artwork is not a body outline
+            r#"SVGNODE~{"nodeName":"g","layerid":"3","attrs":{"c_etype":"logo"},"childNodes":[{"nodeName":"polygon","attrs":{"points":"3900,2900 3950,2900 3950,2950"}}]}"#
+                .to_string(),
+        ])
+        .unwrap();
+        let mut footprint = kicad::KiFootprint {
+            name: "QFN".to_string(),
+            ..Default::default()
+        };
+        let curves = CurveOptions {
+            tolerance: 0.04,
+            native: false,
+        };
+
+        push_svg_nodes(
+            &mut footprint,
+            &ee_footprint,
+            &component_data,
+            &Converter::new(KicadVersion::V8),
+            curves,
+        );
+
+        assert_eq!(ee_footprint.svg_nodes.len(), 3);
+        assert_eq!(footprint.polygons.len(), 1);
+        let body = &footprint.polygons[0];
+        assert_eq!(body.layer, "F.Fab");
+        assert!(!body.fill);
+        assert_eq!(
+            body.points,
+            [(-10.0, -10.0), (10.0, -10.0), (10.0, 10.0), (-10.0, 10.0)]
+        );
+        let lines: Vec<((f64, f64), (f64, f64))> = footprint
+            .lines
+            .iter()
+            .map(|l| ((l.start_x, l.start_y), (l.end_x, l.end_y)))
+            .collect();
+        assert_eq!(lines, [((-5.0, 0.0), (0.0, 0.0)), ((0.0, 0.0), (5.0, 5.0))]);
+        assert!(footprint.lines.iter().all(|l| l.layer == "F.Fab"));
+    }
+
+    #[test]
+    fn recognises_chamfered_rectangle_pads() {
+        // 20 x 10 px with the top-left and bottom-right corners cut by 2 px
+        let points = [
+            (2.0, 0.0),
+            (20.0, 0.0),
+            (20.0, 8.0),
+            (18.0, 10.0),
+            (0.0, 10.0),
+            (0.0, 2.0),
+            (2.0, 0.0),
+        ];
+        let rect = chamfered_rect(&points).unwrap();
+        assert_eq!(rect.center, (10.0, 5.0));
+        assert_eq!(rect.size, (20.0, 10.0));
+        assert_eq!(
+            rect.chamfer,
+            Some(kicad::PadChamfer {
+                ratio: 0.2,
+                top_left: true,
+                top_right: false,
+                bottom_left: false,
+                bottom_right: true,
+            })
+        );
+
+        let plain = chamfered_rect(&[(0.0, 0.0), (4.0, 0.0), (4.0, 2.0), (0.0, 2.0)]).unwrap();
+        assert!(plain.chamfer.is_none());
+
+        // Uneven cut and an L shape stay custom pads
+        let uneven = [
+            (3.0, 0.0),
+            (20.0, 0.0),
+            (20.0, 10.0),
+            (0.0, 10.0),
+            (0.0, 2.0),
+        ];
+        assert!(chamfered_rect(&uneven).is_none());
+        let l_shape = [
+            (0.0, 0.0),
+            (4.0, 0.0),
+            (4.0, 2.0),
+            (2.0, 2.0),
+            (2.0, 4.0),
+            (0.0, 4.0),
+        ];
+        assert!(chamfered_rect(&l_shape).is_none());
+    }
+}
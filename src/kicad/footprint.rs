@@ -1,12 +1,20 @@
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct KiFootprint {
     pub name: String,
+    /// `descr`, e.g. the component description
+    pub description: String,
+    /// Space-separated `tags`, e.g. the package name
+    pub tags: String,
     pub pads: Vec<KiPad>,
     pub tracks: Vec<KiTrack>,
     pub circles: Vec<KiCircle>,
     pub arcs: Vec<KiArc>,
     pub texts: Vec<KiText>,
     pub lines: Vec<KiLine>,
+    pub curves: Vec<KiCurve>,
+    pub rects: Vec<KiRect>,
+    pub polygons: Vec<KiPolygon>,
+    pub zones: Vec<KiZone>,
     pub model_3d: Option<Ki3dModel>,
 }
 
@@ -40,9 +48,10 @@ pub enum PadShape {
 }
 
 impl PadShape {
-    pub fn from_easyeda(shape: &str) -> Self {
+    pub fn from_easyeda(shape: &str, corner_radius: f64) -> Self {
         match shape {
             "ELLIPSE" | "ROUND" => PadShape::Circle,
+            "RECT" if corner_radius > 0.0 => PadShape::RoundRect,
             "RECT" => PadShape::Rect,
             "OVAL" => PadShape::Oval,
             "POLYGON" => PadShape::Custom,
@@ -74,7 +83,21 @@ pub struct KiPad {
     pub rotation: f64,
     pub layers: Vec<String>,
     pub drill: Option<Drill>,
-    pub polygon: Option<String>, // For custom polygon pads
+    pub polygon: Option<Vec<(f64, f64)>>, // Custom pad outline, relative to the pad position
+    /// `roundrect_rratio`: corner radius relative to the shorter side, 0..=0.5
+    pub corner_ratio: f64,
+    pub chamfer: Option<PadChamfer>,
+}
+
+/// Corners of a `roundrect` pad that are cut off at 45°
+#[derive(Debug, Clone, PartialEq)]
+pub struct PadChamfer {
+    /// `chamfer_ratio`: cut size relative to the shorter side, 0..=0.5
+    pub ratio: f64,
+    pub top_left: bool,
+    pub top_right: bool,
+    pub bottom_left: bool,
+    pub bottom_right: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -118,6 +141,53 @@ pub struct KiArc {
     pub layer: String,
 }
 
+/// Cubic Bezier: start, two control points, end
+#[derive(Debug, Clone)]
+pub struct KiCurve {
+    pub points: [(f64, f64); 4],
+    pub width: f64,
+    pub layer: String,
+}
+
+/// Axis-aligned rectangle given by two opposite corners
+#[derive(Debug, Clone)]
+pub struct KiRect {
+    pub start_x: f64,
+    pub start_y: f64,
+    pub end_x: f64,
+    pub end_y: f64,
+    pub width: f64,
+    pub layer: String,
+    pub fill: bool,
+}
+
+/// Closed polygon; the last point connects back to the first
+#[derive(Debug, Clone)]
+pub struct KiPolygon {
+    pub points: Vec<(f64, f64)>,
+    pub width: f64,
+    pub layer: String,
+    pub fill: bool,
+}
+
+/// Rule area (`zone` with `keepout`) inside the footprint
+#[derive(Debug, Clone)]
+pub struct KiZone {
+    pub points: Vec<(f64, f64)>,
+    pub layers: Vec<String>,
+    pub keepout: Keepout,
+}
+
+/// What a keep-out zone forbids; `true` means not allowed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Keepout {
+    pub tracks: bool,
+    pub vias: bool,
+    pub pads: bool,
+    pub copperpour: bool,
+    pub footprints: bool,
+}
+
 #[derive(Debug, Clone)]
 pub struct KiLine {
     pub start_x: f64,
@@ -1,210 +1,761 @@
 use crate::cli::KicadVersion;
-use crate::converter::Converter;
+use crate::converter::{Converter, arc_through};
 use crate::error::Result;
 use crate::kicad::footprint::*;
+use crate::kicad::item_id::ItemIds;
+use crate::kicad::sexpr::SExpr;
 
 pub struct FootprintExporter {
+    version: KicadVersion,
     converter: Converter,
 }
 
 impl FootprintExporter {
-    pub fn new() -> Self {
+    pub fn new(version: KicadVersion) -> Self {
         Self {
-            converter: Converter::new(KicadVersion::V6),
+            version,
+            converter: Converter::new(version),
         }
     }
 
     pub fn export(&self, footprint: &KiFootprint) -> Result<String> {
-        let mut output = String::new();
+        let ids = ItemIds::new(&footprint.name);
 
         // Module header
-        output.push_str(&format!(
-            "(footprint \"{}\" (version 20221018) (generator nlbn)\n",
-            footprint.name
-        ));
-        output.push_str("  (layer \"F.Cu\")\n");
-
-        // Reference and value text
-        output.push_str("  (fp_text reference \"REF**\" (at 0 0) (layer \"F.SilkS\")\n");
-        output.push_str("    (effects (font (size 1 1) (thickness 0.15)))\n");
-        output.push_str("  )\n");
-
-        output.push_str(&format!(
-            "  (fp_text value \"{}\" (at 0 2.5) (layer \"F.Fab\")\n",
-            footprint.name
-        ));
-        output.push_str("    (effects (font (size 1 1) (thickness 0.15)))\n");
-        output.push_str("  )\n");
-
-        // Pads
-        for pad in &footprint.pads {
-            output.push_str(&self.format_pad(pad));
+        let mut sexpr = if self.version == KicadVersion::V5 {
+            SExpr::list("module")
+                .str(&footprint.name)
+                .str_field("layer", "F.Cu")
+                .item(SExpr::list("tedit").int(0))
+        } else {
+            let mut header = SExpr::list("footprint")
+                .str(&footprint.name)
+                .item(SExpr::list("version").int(self.version.footprint_format().into()))
+                .sym_field("generator", "nlbn");
+            if let Some(generator_version) = self.version.generator_version() {
+                header = header.str_field("generator_version", generator_version);
+            }
+            header.str_field("layer", "F.Cu")
+        };
+        if !footprint.description.is_empty() {
+            sexpr = sexpr.str_field("descr", &footprint.description);
         }
-
-        // Lines
-        for line in &footprint.lines {
-            output.push_str(&self.format_line(line));
+        if !footprint.tags.is_empty() {
+            sexpr = sexpr.str_field("tags", &footprint.tags);
         }
+        let attr = self.format_attr(footprint);
 
-        // Circles
-        for circle in &footprint.circles {
-            output.push_str(&self.format_circle(circle));
+        // Reference and value text
+        if self.version >= KicadVersion::V8 {
+            let fields = [
+                ("Reference", "REF**", 0.0, "F.SilkS", false),
+                ("Value", footprint.name.as_str(), 2.5, "F.Fab", false),
+                ("Footprint", "", 0.0, "F.Fab", true),
+                ("Datasheet", "", 0.0, "F.Fab", true),
+                ("Description", "", 0.0, "F.Fab", true),
+            ];
+            for (name, value, y, layer, hidden) in fields {
+                let mut property = SExpr::list("property")
+                    .str(name)
+                    .str(value)
+                    .item(SExpr::point("at", 0.0, y).num(0.0))
+                    .str_field("layer", layer);
+                if hidden {
+                    property = property.flag("hide", true);
+                }
+                sexpr = sexpr.item(
+                    property
+                        .items(self.item_id(&ids))
+                        .item(font_effects(1.0, 0.15)),
+                );
+            }
+            // KiCad 8 writes the attributes after the properties
+            sexpr = sexpr.items(attr);
+        } else {
+            sexpr = sexpr.items(attr);
+            for (kind, text, y, layer) in [
+                ("reference", "REF**", 0.0, "F.SilkS"),
+                ("value", footprint.name.as_str(), 2.5, "F.Fab"),
+            ] {
+                sexpr = sexpr.item(
+                    SExpr::list("fp_text")
+                        .sym(kind)
+                        .str(text)
+                        .item(SExpr::point("at", 0.0, y))
+                        .str_field("layer", layer)
+                        .items(self.item_id(&ids))
+                        .item(font_effects(1.0, 0.15)),
+                );
+            }
         }
 
-        // Arcs
-        for arc in &footprint.arcs {
-            output.push_str(&self.format_arc(arc));
+        sexpr = sexpr
+            .items(footprint.pads.iter().map(|pad| self.format_pad(pad, &ids)))
+            .items(
+                footprint
+                    .lines
+                    .iter()
+                    .map(|line| self.format_line(line, &ids)),
+            )
+            .items(
+                footprint
+                    .curves
+                    .iter()
+                    .map(|curve| self.format_curve(curve, &ids)),
+            )
+            .items(
+                footprint
+                    .circles
+                    .iter()
+                    .map(|circle| self.format_circle(circle, &ids)),
+            )
+            .items(footprint.arcs.iter().map(|arc| self.format_arc(arc, &ids)))
+            .items(
+                footprint
+                    .rects
+                    .iter()
+                    .flat_map(|rect| self.format_rect(rect, &ids)),
+            )
+            .items(
+                footprint
+                    .polygons
+                    .iter()
+                    .flat_map(|poly| self.format_polygon(poly, &ids)),
+            )
+            .items(
+                footprint
+                    .texts
+                    .iter()
+                    .map(|text| self.format_text(text, &ids)),
+            );
+
+        // KiCad 5 footprints cannot hold zones
+        if self.version == KicadVersion::V5 {
+            if !footprint.zones.is_empty() {
+                log::debug!(
+                    "Dropping {} keep-out zone(s) of {} for KiCad 5",
+                    footprint.zones.len(),
+                    footprint.name
+                );
+            }
+        } else {
+            sexpr = sexpr.items(
+                footprint
+                    .zones
+                    .iter()
+                    .map(|zone| self.format_zone(zone, &ids)),
+            );
         }
 
-        // Texts
-        for text in &footprint.texts {
-            output.push_str(&self.format_text(text));
+        if self.version >= KicadVersion::V9 {
+            sexpr = sexpr.flag("embedded_fonts", false);
         }
 
         // 3D model
         if let Some(model) = &footprint.model_3d {
-            output.push_str(&self.format_3d_model(model));
+            sexpr = sexpr.item(self.format_3d_model(model));
         }
 
-        output.push_str(")\n");
-
-        Ok(output)
+        Ok(format!("{}\n", sexpr.render(0)))
     }
 
-    fn format_pad(&self, pad: &KiPad) -> String {
-        let x = self.converter.px_to_mm(pad.pos_x);
-        let y = self.converter.px_to_mm(pad.pos_y); // No flip_y for footprints
-        let size_x = self.converter.px_to_mm(pad.size_x);
-        let size_y = self.converter.px_to_mm(pad.size_y);
+    /// Mounting type from the pads: through-hole if any pad is plated
+    /// through, SMD if any is SMD. Footprints with only NPTH holes or
+    /// graphics are kept out of the BOM, position files and schematic.
+    fn format_attr(&self, footprint: &KiFootprint) -> Option<SExpr> {
+        let has_pad = |pad_type: PadType| footprint.pads.iter().any(|p| p.pad_type == pad_type);
+        let mounting = if has_pad(PadType::ThroughHole) {
+            Some("through_hole")
+        } else if has_pad(PadType::Smd) {
+            Some("smd")
+        } else {
+            None
+        };
+
+        // KiCad 5 defaults to through-hole and calls board-only footprints virtual
+        if self.version == KicadVersion::V5 {
+            return match mounting {
+                Some("through_hole") => None,
+                Some(kind) => Some(SExpr::list("attr").sym(kind)),
+                None => Some(SExpr::list("attr").sym("virtual")),
+            };
+        }
 
-        let mut output = format!(
-            "  (pad \"{}\" {} {} (at {:.4} {:.4}",
-            pad.number,
-            pad.pad_type.to_kicad(),
-            pad.shape.to_kicad(),
-            x,
-            y
-        );
+        Some(match mounting {
+            Some(kind) => SExpr::list("attr").sym(kind),
+            None => SExpr::list("attr")
+                .sym("board_only")
+                .sym("exclude_from_pos_files")
+                .sym("exclude_from_bom"),
+        })
+    }
 
-        if pad.rotation != 0.0 {
-            output.push_str(&format!(" {:.4}", pad.rotation));
+    /// Item id: `tstamp` in KiCad 7, `uuid` since KiCad 8, nothing before
+    fn item_id(&self, ids: &ItemIds) -> Option<SExpr> {
+        match self.version {
+            KicadVersion::V5 | KicadVersion::V6 => None,
+            KicadVersion::V7 => Some(SExpr::list("tstamp").sym(&ids.next_id())),
+            KicadVersion::V8 | KicadVersion::V9 => Some(SExpr::list("uuid").str(&ids.next_id())),
         }
+    }
 
-        output.push_str(&format!(") (size {:.4} {:.4})", size_x, size_y));
-
-        // Layers
-        output.push_str(" (layers");
-        for layer in &pad.layers {
-            output.push_str(&format!(" \"{}\"", layer));
+    fn format_pad(&self, pad: &KiPad, ids: &ItemIds) -> SExpr {
+        let mut at = self.point("at", pad.pos_x, pad.pos_y); // No flip_y for footprints
+        if pad.rotation != 0.0 {
+            at = at.num(pad.rotation);
         }
-        output.push_str(")");
+
+        let mut sexpr = SExpr::list("pad")
+            .str(&pad.number)
+            .sym(pad.pad_type.to_kicad())
+            .sym(pad.shape.to_kicad())
+            .item(at)
+            .item(self.point("size", pad.size_x, pad.size_y))
+            .item(SExpr::list("layers").items(pad.layers.iter().map(|l| SExpr::Str(l.clone()))));
 
         // Drill
         if let Some(drill) = &pad.drill {
             let drill_dia = self.converter.px_to_mm(drill.diameter);
-            if let Some(width) = drill.width {
+            sexpr = sexpr.item(match drill.width {
                 // Elliptical drill
-                let drill_width = self.converter.px_to_mm(width);
-                output.push_str(&format!(
-                    " (drill oval {:.4} {:.4})",
-                    drill_dia, drill_width
-                ));
-            } else {
+                Some(width) => SExpr::list("drill")
+                    .sym("oval")
+                    .num(drill_dia)
+                    .num(self.converter.px_to_mm(width)),
                 // Circular drill
-                output.push_str(&format!(" (drill {:.4})", drill_dia));
+                None => SExpr::list("drill").num(drill_dia),
+            });
+        }
+
+        // Corner radius and 45° cut corners
+        if pad.shape == PadShape::RoundRect {
+            sexpr = sexpr.num_field("roundrect_rratio", pad.corner_ratio);
+            if let Some(chamfer) = &pad.chamfer {
+                let corners = [
+                    ("top_left", chamfer.top_left),
+                    ("top_right", chamfer.top_right),
+                    ("bottom_left", chamfer.bottom_left),
+                    ("bottom_right", chamfer.bottom_right),
+                ];
+                let corners = corners.into_iter().filter(|&(_, cut)| cut);
+                sexpr = sexpr.num_field("chamfer_ratio", chamfer.ratio).item(
+                    SExpr::list("chamfer")
+                        .items(corners.map(|(name, _)| SExpr::Symbol(name.into()))),
+                );
             }
         }
 
         // Polygon (for custom pads)
         if let Some(polygon) = &pad.polygon {
-            output.push_str(polygon);
+            let pts = polygon.iter().map(|&(x, y)| self.point("xy", x, y));
+            sexpr = sexpr.item(
+                SExpr::list("primitives").item(
+                    SExpr::list("gr_poly")
+                        .item(SExpr::list("pts").items(pts))
+                        .num_field("width", 0.1),
+                ),
+            );
         }
 
-        output.push_str(")\n");
-
-        output
+        sexpr.items(self.item_id(ids))
     }
 
-    fn format_line(&self, line: &KiLine) -> String {
-        let start_x = self.converter.px_to_mm(line.start_x);
-        let start_y = self.converter.px_to_mm(line.start_y); // No flip_y for footprints
-        let end_x = self.converter.px_to_mm(line.end_x);
-        let end_y = self.converter.px_to_mm(line.end_y); // No flip_y for footprints
-        let width = self.converter.px_to_mm(line.width);
+    fn format_line(&self, line: &KiLine, ids: &ItemIds) -> SExpr {
+        // No flip_y for footprints
+        SExpr::list("fp_line")
+            .item(self.point("start", line.start_x, line.start_y))
+            .item(self.point("end", line.end_x, line.end_y))
+            .items(self.stroke(self.converter.px_to_mm(line.width), None, &line.layer, ids))
+    }
 
-        format!(
-            "  (fp_line (start {:.4} {:.4}) (end {:.4} {:.4})\n    (stroke (width {:.4}) (type solid)) (layer \"{}\")\n  )\n",
-            start_x, start_y, end_x, end_y, width, line.layer
-        )
+    fn format_curve(&self, curve: &KiCurve, ids: &ItemIds) -> SExpr {
+        let pts = curve.points.iter().map(|&(x, y)| self.point("xy", x, y));
+
+        SExpr::list("fp_curve")
+            .item(SExpr::list("pts").items(pts))
+            .items(self.stroke(
+                self.converter.px_to_mm(curve.width),
+                None,
+                &curve.layer,
+                ids,
+            ))
     }
 
-    fn format_circle(&self, circle: &KiCircle) -> String {
-        let center_x = self.converter.px_to_mm(circle.center_x);
-        let center_y = self.converter.px_to_mm(circle.center_y); // No flip_y for footprints
-        let end_x = self.converter.px_to_mm(circle.end_x);
-        let end_y = self.converter.px_to_mm(circle.end_y); // No flip_y for footprints
+    fn format_circle(&self, circle: &KiCircle, ids: &ItemIds) -> SExpr {
         let width = self.converter.px_to_mm(circle.width);
+        // No flip_y for footprints
+        SExpr::list("fp_circle")
+            .item(self.point("center", circle.center_x, circle.center_y))
+            .item(self.point("end", circle.end_x, circle.end_y))
+            .items(self.stroke(width, Some(circle.fill), &circle.layer, ids))
+    }
 
-        let fill = if circle.fill { "solid" } else { "none" };
+    fn format_arc(&self, arc: &KiArc, ids: &ItemIds) -> SExpr {
+        let width = self.converter.px_to_mm(arc.width);
+
+        if self.version == KicadVersion::V5 {
+            return self.format_arc_v5(arc, width, ids);
+        }
 
-        format!(
-            "  (fp_circle (center {:.4} {:.4}) (end {:.4} {:.4})\n    (stroke (width {:.4}) (type solid)) (fill {}) (layer \"{}\")\n  )\n",
-            center_x, center_y, end_x, end_y, width, fill, circle.layer
-        )
+        // No flip_y for footprints
+        SExpr::list("fp_arc")
+            .item(self.point("start", arc.start_x, arc.start_y))
+            .item(self.point("mid", arc.mid_x, arc.mid_y))
+            .item(self.point("end", arc.end_x, arc.end_y))
+            .items(self.stroke(width, None, &arc.layer, ids))
     }
 
-    fn format_arc(&self, arc: &KiArc) -> String {
-        let start_x = self.converter.px_to_mm(arc.start_x);
-        let start_y = self.converter.px_to_mm(arc.start_y); // No flip_y for footprints
-        let mid_x = self.converter.px_to_mm(arc.mid_x);
-        let mid_y = self.converter.px_to_mm(arc.mid_y); // No flip_y for footprints
-        let end_x = self.converter.px_to_mm(arc.end_x);
-        let end_y = self.converter.px_to_mm(arc.end_y); // No flip_y for footprints
-        let width = self.converter.px_to_mm(arc.width);
+    /// KiCad 5 arcs are a center, a start point and a clockwise sweep angle
+    fn format_arc_v5(&self, arc: &KiArc, width: f64, ids: &ItemIds) -> SExpr {
+        let start = (arc.start_x, arc.start_y);
+        let end = (arc.end_x, arc.end_y);
+        let Some((center, _, sweep)) = arc_through(start, (arc.mid_x, arc.mid_y), end) else {
+            // Degenerate arc: draw the chord
+            return self.format_line(
+                &KiLine {
+                    start_x: arc.start_x,
+                    start_y: arc.start_y,
+                    end_x: arc.end_x,
+                    end_y: arc.end_y,
+                    width: arc.width,
+                    layer: arc.layer.clone(),
+                },
+                ids,
+            );
+        };
+
+        // Y points down, so a positive sweep is clockwise as KiCad 5 expects
+        SExpr::list("fp_arc")
+            .item(self.point("start", center.0, center.1))
+            .item(self.point("end", start.0, start.1))
+            .num_field("angle", sweep)
+            .items(self.stroke(width, None, &arc.layer, ids))
+    }
+
+    /// `fp_rect`; KiCad 5 has no rectangles, so it gets four lines
+    fn format_rect(&self, rect: &KiRect, ids: &ItemIds) -> Vec<SExpr> {
+        if self.version == KicadVersion::V5 {
+            let corners = [
+                (rect.start_x, rect.start_y),
+                (rect.end_x, rect.start_y),
+                (rect.end_x, rect.end_y),
+                (rect.start_x, rect.end_y),
+            ];
+            return (0..4)
+                .map(|i| {
+                    let (start, end) = (corners[i], corners[(i + 1) % 4]);
+                    self.format_line(
+                        &KiLine {
+                            start_x: start.0,
+                            start_y: start.1,
+                            end_x: end.0,
+                            end_y: end.1,
+                            width: rect.width,
+                            layer: rect.layer.clone(),
+                        },
+                        ids,
+                    )
+                })
+                .collect();
+        }
+
+        vec![
+            SExpr::list("fp_rect")
+                .item(self.point("start", rect.start_x, rect.start_y))
+                .item(self.point("end", rect.end_x, rect.end_y))
+                .items(self.stroke(
+                    self.converter.px_to_mm(rect.width),
+                    Some(rect.fill),
+                    &rect.layer,
+                    ids,
+                )),
+        ]
+    }
+
+    /// `fp_poly`; KiCad 5 always fills polygons, so unfilled ones become a
+    /// closed run of lines
+    fn format_polygon(&self, polygon: &KiPolygon, ids: &ItemIds) -> Vec<SExpr> {
+        if self.version == KicadVersion::V5 && !polygon.fill {
+            let points = &polygon.points;
+            return (0..points.len())
+                .map(|i| {
+                    let (start, end) = (points[i], points[(i + 1) % points.len()]);
+                    self.format_line(
+                        &KiLine {
+                            start_x: start.0,
+                            start_y: start.1,
+                            end_x: end.0,
+                            end_y: end.1,
+                            width: polygon.width,
+                            layer: polygon.layer.clone(),
+                        },
+                        ids,
+                    )
+                })
+                .collect();
+        }
+
+        let pts = polygon.points.iter().map(|&(x, y)| self.point("xy", x, y));
+        let width = self.converter.px_to_mm(polygon.width);
+        vec![
+            SExpr::list("fp_poly")
+                .item(SExpr::list("pts").items(pts))
+                .items(self.stroke(width, Some(polygon.fill), &polygon.layer, ids)),
+        ]
+    }
+
+    /// Keep-out rule area; zones are unconnected and never filled
+    fn format_zone(&self, zone: &KiZone, ids: &ItemIds) -> SExpr {
+        let layers = match &zone.layers[..] {
+            [layer] => SExpr::list("layer").str(layer),
+            layers => SExpr::list("layers").items(layers.iter().map(|l| SExpr::Str(l.clone()))),
+        };
+        let rule = |head: &str, forbidden: bool| {
+            SExpr::list(head).sym(if forbidden { "not_allowed" } else { "allowed" })
+        };
+        let keepout = SExpr::list("keepout")
+            .item(rule("tracks", zone.keepout.tracks))
+            .item(rule("vias", zone.keepout.vias))
+            .item(rule("pads", zone.keepout.pads))
+            .item(rule("copperpour", zone.keepout.copperpour))
+            .item(rule("footprints", zone.keepout.footprints));
+        let pts = zone.points.iter().map(|&(x, y)| self.point("xy", x, y));
+
+        SExpr::list("zone")
+            .item(SExpr::list("net").int(0))
+            .str_field("net_name", "")
+            .item(layers)
+            .items(self.item_id(ids))
+            .item(SExpr::list("hatch").sym("edge").num(0.5))
+            .item(SExpr::list("connect_pads").item(SExpr::list("clearance").int(0)))
+            .num_field("min_thickness", 0.25)
+            .item(keepout)
+            .item(
+                SExpr::list("fill")
+                    .num_field("thermal_gap", 0.5)
+                    .num_field("thermal_bridge_width", 0.5),
+            )
+            .item(SExpr::list("polygon").item(SExpr::list("pts").items(pts)))
+    }
 
-        format!(
-            "  (fp_arc (start {:.4} {:.4}) (mid {:.4} {:.4}) (end {:.4} {:.4})\n    (stroke (width {:.4}) (type solid)) (layer \"{}\")\n  )\n",
-            start_x, start_y, mid_x, mid_y, end_x, end_y, width, arc.layer
-        )
+    /// Stroke, fill and layer of a graphic item. KiCad 5 and 6 write a plain
+    /// `(width w)` after the layer; KiCad 5 cannot fill circles and always
+    /// fills polygons, so it gets no `fill`
+    fn stroke(&self, width: f64, fill: Option<bool>, layer: &str, ids: &ItemIds) -> Vec<SExpr> {
+        let layer = SExpr::list("layer").str(layer);
+        let fill = fill
+            .filter(|_| self.version != KicadVersion::V5)
+            .map(|fill| SExpr::list("fill").sym(if fill { "solid" } else { "none" }));
+
+        let mut items = if self.version <= KicadVersion::V6 {
+            let mut items = vec![layer, SExpr::list("width").num(width)];
+            items.extend(fill);
+            items
+        } else {
+            let mut items = vec![stroke(width)];
+            items.extend(fill);
+            items.push(layer);
+            items
+        };
+        items.extend(self.item_id(ids));
+        items
     }
 
-    fn format_text(&self, text: &KiText) -> String {
-        let x = self.converter.px_to_mm(text.pos_x);
-        let y = self.converter.px_to_mm(text.pos_y); // No flip_y for footprints
+    fn format_text(&self, text: &KiText, ids: &ItemIds) -> SExpr {
+        let mut at = self.point("at", text.pos_x, text.pos_y); // No flip_y for footprints
+        if text.rotation != 0.0 {
+            at = at.num(text.rotation);
+        }
         let size = self.converter.px_to_mm(text.size);
         let thickness = self.converter.px_to_mm(text.thickness);
 
-        format!("  (fp_text user \"{}\" (at {:.4} {:.4}", text.text, x, y)
-            + &(if text.rotation != 0.0 {
-                format!(" {:.4}", text.rotation)
-            } else {
-                String::new()
-            })
-            + &format!(
-                ") (layer \"{}\")\n    (effects (font (size {:.4} {:.4}) (thickness {:.4})))\n  )\n",
-                text.layer, size, size, thickness
-            )
+        SExpr::list("fp_text")
+            .sym("user")
+            .str(&text.text)
+            .item(at)
+            .str_field("layer", &text.layer)
+            .items(self.item_id(ids))
+            .item(font_effects(size, thickness))
     }
 
-    fn format_3d_model(&self, model: &Ki3dModel) -> String {
-        format!(
-            "  (model \"{}\"\n    (offset (xyz {:.4} {:.4} {:.4}))\n    (scale (xyz {:.4} {:.4} {:.4}))\n    (rotate (xyz {:.4} {:.4} {:.4}))\n  )\n",
-            model.path,
-            model.offset.0,
-            model.offset.1,
-            model.offset.2,
-            model.scale.0,
-            model.scale.1,
-            model.scale.2,
-            model.rotate.0,
-            model.rotate.1,
-            model.rotate.2
-        )
+    fn format_3d_model(&self, model: &Ki3dModel) -> SExpr {
+        let xyz = |head: &str, (x, y, z): (f64, f64, f64)| {
+            SExpr::list(head).item(SExpr::list("xyz").num(x).num(y).num(z))
+        };
+
+        SExpr::list("model")
+            .str(&model.path)
+            .item(xyz("offset", model.offset))
+            .item(xyz("scale", model.scale))
+            .item(xyz("rotate", model.rotate))
+    }
+
+    /// `(head x y)` with a px position converted to mm
+    fn point(&self, head: &str, x: f64, y: f64) -> SExpr {
+        SExpr::point(head, self.converter.px_to_mm(x), self.converter.px_to_mm(y))
     }
 }
 
+/// `(stroke (width w) (type solid))`
+fn stroke(width: f64) -> SExpr {
+    SExpr::list("stroke")
+        .num_field("width", width)
+        .sym_field("type", "solid")
+}
+
+/// `(effects (font (size s s) (thickness t)))`
+fn font_effects(size: f64, thickness: f64) -> SExpr {
+    SExpr::list("effects").item(
+        SExpr::list("font")
+            .item(SExpr::point("size", size, size))
+            .num_field("thickness", thickness),
+    )
+}
+
 impl Default for FootprintExporter {
     fn default() -> Self {
-        Self::new()
+        Self::new(KicadVersion::V6)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn footprint() -> KiFootprint {
+        KiFootprint {
+            name: "SOT-23_C2040".to_string(),
+            lines: vec![KiLine {
+                start_x: 0.0,
+                start_y: 0.0,
+                end_x: 10.0,
+                end_y: 0.0,
+                width: 1.0,
+                layer: "F.SilkS".to_string(),
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn writes_native_kicad8_footprints() {
+        let output = FootprintExporter::new(KicadVersion::V8)
+            .export(&footprint())
+            .unwrap();
+
+        assert!(output.starts_with(
+            "(footprint \"SOT-23_C2040\"\n  (version 20240108)\n  (generator nlbn)\n  (generator_version \"8.0\")\n"
+        ));
+        assert!(output.contains("(property \"Reference\" \"REF**\""));
+        assert!(output.contains(
+            "(property \"Datasheet\" \"\"\n    (at 0 0 0)\n    (layer \"F.Fab\")\n    (hide yes)\n    (uuid"
+        ));
+        assert!(!output.contains("fp_text"));
+        // Reference, Value, Footprint, Datasheet, Description and the line
+        assert_eq!(output.matches("(uuid \"").count(), 6);
+        assert_eq!(
+            output,
+            FootprintExporter::new(KicadVersion::V8)
+                .export(&footprint())
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn kicad5_footprints_use_module_syntax() {
+        let mut footprint = footprint();
+        // Half circle around (10, 0), clockwise on screen through (10, -10)
+        footprint.arcs.push(KiArc {
+            start_x: 0.0,
+            start_y: 0.0,
+            mid_x: 10.0,
+            mid_y: -10.0,
+            end_x: 20.0,
+            end_y: 0.0,
+            width: 1.0,
+            layer: "F.SilkS".to_string(),
+        });
+        footprint.polygons.push(KiPolygon {
+            points: vec![(0.0, 0.0), (10.0, 0.0), (10.0, 10.0)],
+            width: 1.0,
+            layer: "F.CrtYd".to_string(),
+            fill: false,
+        });
+
+        let output = FootprintExporter::new(KicadVersion::V5)
+            .export(&footprint)
+            .unwrap();
+
+        assert!(output.starts_with("(module \"SOT-23_C2040\"\n  (layer \"F.Cu\")\n  (tedit 0)\n"));
+        assert!(
+            output.contains(
+                "  (fp_line (start 0 0) (end 2.54 0) (layer \"F.SilkS\") (width 0.254))\n"
+            )
+        );
+        assert!(output.contains(
+            "  (fp_arc (start 2.54 0) (end 0 0) (angle 180) (layer \"F.SilkS\") (width 0.254))\n"
+        ));
+        // KiCad 5 fills every fp_poly, so an unfilled polygon is drawn as lines
+        assert!(!output.contains("fp_poly"));
+        assert_eq!(output.matches("(layer \"F.CrtYd\")").count(), 3);
+        assert!(output.contains(
+            "  (fp_line (start 2.54 2.54) (end 0 0) (layer \"F.CrtYd\") (width 0.254))\n"
+        ));
+        assert!(!output.contains("stroke") && !output.contains("(version"));
+    }
+
+    #[test]
+    fn kicad6_footprints_have_no_item_ids() {
+        let output = FootprintExporter::new(KicadVersion::V6)
+            .export(&footprint())
+            .unwrap();
+
+        assert!(output.contains(
+            "(fp_text reference \"REF**\"\n    (at 0 0)\n    (layer \"F.SilkS\")\n    (effects"
+        ));
+        assert!(!output.contains("uuid") && !output.contains("tstamp"));
+    }
+
+    #[test]
+    fn kicad6_footprints_use_width_syntax() {
+        let mut footprint = footprint();
+        footprint.polygons.push(KiPolygon {
+            points: vec![(0.0, 0.0), (10.0, 0.0), (10.0, 10.0)],
+            width: 0.0,
+            layer: "F.Cu".to_string(),
+            fill: true,
+        });
+
+        let output = FootprintExporter::new(KicadVersion::V6)
+            .export(&footprint)
+            .unwrap();
+
+        assert!(output.starts_with("(footprint \"SOT-23_C2040\"\n  (version 20211014)\n"));
+        assert!(
+            output.contains(
+                "  (fp_line (start 0 0) (end 2.54 0) (layer \"F.SilkS\") (width 0.254))\n"
+            )
+        );
+        assert!(output.contains(
+            "  (fp_poly (pts (xy 0 0) (xy 2.54 0) (xy 2.54 2.54)) (layer \"F.Cu\") (width 0) (fill solid))\n"
+        ));
+        assert!(!output.contains("stroke"));
+    }
+
+    #[test]
+    fn attributes_follow_pad_types() {
+        let pad = |pad_type: PadType| KiPad {
+            number: "1".to_string(),
+            pad_type,
+            shape: PadShape::Circle,
+            pos_x: 0.0,
+            pos_y: 0.0,
+            size_x: 4.0,
+            size_y: 4.0,
+            rotation: 0.0,
+            layers: vec!["F.Cu".to_string()],
+            drill: None,
+            polygon: None,
+            corner_ratio: 0.0,
+            chamfer: None,
+        };
+        let export = |version: KicadVersion, pads: Vec<KiPad>| {
+            let mut footprint = footprint();
+            footprint.description = "SOT-23 \"small\" transistor".to_string();
+            footprint.tags = "SOT-23-3".to_string();
+            footprint.pads = pads;
+            FootprintExporter::new(version).export(&footprint).unwrap()
+        };
+
+        let smd = export(KicadVersion::V7, vec![pad(PadType::Smd)]);
+        assert!(smd.contains(
+            "  (layer \"F.Cu\")\n  (descr \"SOT-23 \\\"small\\\" transistor\")\n  (tags \"SOT-23-3\")\n  (attr smd)\n  (fp_text reference"
+        ));
+
+        let mixed = export(
+            KicadVersion::V8,
+            vec![pad(PadType::Smd), pad(PadType::ThroughHole)],
+        );
+        assert!(mixed.contains("  )\n  (attr through_hole)\n  (pad "));
+
+        let holes = export(KicadVersion::V9, vec![pad(PadType::NpThroughHole)]);
+        assert!(holes.contains("(attr board_only exclude_from_pos_files exclude_from_bom)"));
+
+        assert!(export(KicadVersion::V5, Vec::new()).contains("(attr virtual)"));
+        assert!(!export(KicadVersion::V5, vec![pad(PadType::ThroughHole)]).contains("(attr"));
+    }
+
+    #[test]
+    fn writes_rounded_and_chamfered_pads() {
+        let pad = KiPad {
+            number: "1".to_string(),
+            pad_type: PadType::Smd,
+            shape: PadShape::RoundRect,
+            pos_x: 0.0,
+            pos_y: 0.0,
+            size_x: 10.0,
+            size_y: 5.0,
+            rotation: 0.0,
+            layers: vec!["F.Cu".to_string()],
+            drill: None,
+            polygon: None,
+            corner_ratio: 0.25,
+            chamfer: None,
+        };
+        let chamfered = KiPad {
+            corner_ratio: 0.0,
+            chamfer: Some(PadChamfer {
+                ratio: 0.2,
+                top_left: true,
+                top_right: false,
+                bottom_left: false,
+                bottom_right: true,
+            }),
+            ..pad.clone()
+        };
+        let mut footprint = footprint();
+        footprint.pads = vec![pad, chamfered];
+
+        let output = FootprintExporter::new(KicadVersion::V6)
+            .export(&footprint)
+            .unwrap();
+
+        assert!(output.contains(
+            "(pad \"1\" smd roundrect (at 0 0) (size 2.54 1.27) (layers \"F.Cu\") (roundrect_rratio 0.25))"
+        ));
+        assert!(
+            output.contains(
+                "    (roundrect_rratio 0)\n    (chamfer_ratio 0.2)\n    (chamfer top_left bottom_right)\n  )"
+            )
+        );
+    }
+
+    #[test]
+    fn writes_keepout_zones() {
+        let mut footprint = footprint();
+        footprint.zones.push(KiZone {
+            points: vec![(0.0, 0.0), (10.0, 0.0), (10.0, 10.0)],
+            layers: vec!["F.Cu".to_string(), "B.Cu".to_string()],
+            keepout: Keepout {
+                copperpour: true,
+                ..Default::default()
+            },
+        });
+
+        let output = FootprintExporter::new(KicadVersion::V8)
+            .export(&footprint)
+            .unwrap();
+        assert!(output.contains(
+            "  (zone\n    (net 0)\n    (net_name \"\")\n    (layers \"F.Cu\" \"B.Cu\")\n    (uuid "
+        ));
+        assert!(output.contains(
+            "    (keepout\n      (tracks allowed)\n      (vias allowed)\n      (pads allowed)\n      (copperpour not_allowed)\n      (footprints allowed)\n    )\n"
+        ));
+        assert!(output.contains("    (polygon (pts (xy 0 0) (xy 2.54 0) (xy 2.54 2.54)))\n  )"));
+
+        let v5 = FootprintExporter::new(KicadVersion::V5)
+            .export(&footprint)
+            .unwrap();
+        assert!(!v5.contains("(zone"));
     }
 }
@@ -0,0 +1,71 @@
+use std::cell::Cell;
+
+/// Generates the `uuid`s of the items in one exported file.
+///
+/// The ids are derived from a seed (the footprint or symbol name) and a
+/// running counter instead of a random source, so converting the same part
+/// again produces a byte-identical file.
+pub struct ItemIds {
+    seed: String,
+    counter: Cell<u64>,
+}
+
+impl ItemIds {
+    pub fn new(seed: &str) -> Self {
+        Self {
+            seed: seed.to_string(),
+            counter: Cell::new(0),
+        }
+    }
+
+    /// Next id, formatted as a version 4 UUID
+    pub fn next_id(&self) -> String {
+        let n = self.counter.get();
+        self.counter.set(n + 1);
+
+        let high = fnv1a(0xcbf2_9ce4_8422_2325, self.seed.as_bytes(), n);
+        let low = fnv1a(high ^ 0x9e37_79b9_7f4a_7c15, self.seed.as_bytes(), n);
+        // Version 4 and RFC 4122 variant bits
+        const VERSION_MASK: u128 = (0xf000 << 64) | (0xc000 << 48);
+        const VERSION_BITS: u128 = (0x4000 << 64) | (0x8000 << 48);
+        let bits = ((((high as u128) << 64) | low as u128) & !VERSION_MASK) | VERSION_BITS;
+
+        let hex = format!("{:032x}", bits);
+        format!(
+            "{}-{}-{}-{}-{}",
+            &hex[0..8],
+            &hex[8..12],
+            &hex[12..16],
+            &hex[16..20],
+            &hex[20..32]
+        )
+    }
+}
+
+/// 64-bit FNV-1a over `bytes` followed by `n`
+fn fnv1a(offset: u64, bytes: &[u8], n: u64) -> u64 {
+    bytes
+        .iter()
+        .chain(n.to_le_bytes().iter())
+        .fold(offset, |hash, &b| {
+            (hash ^ b as u64).wrapping_mul(0x0000_0100_0000_01b3)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ids_are_unique_and_reproducible() {
+        let ids = ItemIds::new("SOT-23_C2040");
+        let first: Vec<String> = (0..3).map(|_| ids.next_id()).collect();
+        let again = ItemIds::new("SOT-23_C2040");
+
+        assert_eq!(first[0], again.next_id());
+        assert_ne!(first[0], first[1]);
+        assert_eq!(first[2].len(), 36);
+        assert_eq!(&first[2][14..15], "4");
+        assert_ne!(first[0], ItemIds::new("SOT-23_C2041").next_id());
+    }
+}
@@ -1,5 +1,4 @@
-/// KiCad layer mapping for EasyEDA footprints
-/// Map EasyEDA layer ID to KiCad layer name
+//! KiCad layer mapping for EasyEDA footprints
 
 /// Map EasyEDA layer ID to KiCad layer name for general graphics
 pub fn map_layer(layer_id: i32) -> String {
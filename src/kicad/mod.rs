@@ -1,18 +1,25 @@
 pub mod footprint;
 pub mod footprint_exporter;
+pub mod item_id;
 pub mod layers;
 pub mod model_exporter;
+pub mod sexpr;
 pub mod symbol;
 pub mod symbol_exporter;
 
 pub use footprint::{
-    Drill, Ki3dModel, KiArc as FootprintKiArc, KiCircle as FootprintKiCircle, KiFootprint, KiLine,
-    KiPad, KiText, KiTrack, PadShape, PadType,
+    Drill, Keepout, Ki3dModel, KiArc as FootprintKiArc, KiCircle as FootprintKiCircle, KiCurve,
+    KiFootprint, KiLine, KiPad, KiPolygon, KiRect, KiText, KiTrack, KiZone, PadChamfer, PadShape,
+    PadType,
 };
 pub use footprint_exporter::FootprintExporter;
+pub use item_id::ItemIds;
 pub use layers::*;
 pub use model_exporter::ModelExporter;
+pub use sexpr::SExpr;
 pub use symbol::KiArc as SymbolKiArc;
 pub use symbol::KiText as SymbolKiText;
-pub use symbol::{KiCircle, KiPin, KiPolyline, KiRectangle, KiSymbol, PinStyle, PinType};
+pub use symbol::{
+    KiBezier, KiCircle, KiPin, KiPolyline, KiRectangle, KiSymbol, KiSymbolUnit, PinStyle, PinType,
+};
 pub use symbol_exporter::SymbolExporter;
@@ -0,0 +1,229 @@
+use std::fmt;
+
+/// Lists whose flat form is longer than this are broken over several lines
+const LINE_WIDTH: usize = 100;
+
+/// Fraction digits of numbers written with [`SExpr::num`]
+const DEFAULT_DECIMALS: u8 = 4;
+
+/// A KiCad S-expression.
+///
+/// Exporters build a tree of these and render it with [`SExpr::render`], so
+/// strings are always escaped and numbers and line breaks are formatted the
+/// same way in every file.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SExpr {
+    /// Unquoted token such as `footprint`, `smd` or `yes`
+    Symbol(String),
+    /// Quoted string; quotes, backslashes and control characters are escaped
+    Str(String),
+    /// Number with at most the given count of fraction digits
+    Num(f64, u8),
+    List(Vec<SExpr>),
+}
+
+impl SExpr {
+    /// A list starting with the keyword `head`
+    pub fn list(head: &str) -> Self {
+        SExpr::List(vec![SExpr::Symbol(head.to_string())])
+    }
+
+    /// `(head x y)`, e.g. `(xy 1.27 0)` or `(start 0 0)`
+    pub fn point(head: &str, x: f64, y: f64) -> Self {
+        SExpr::list(head).num(x).num(y)
+    }
+
+    /// Append any item
+    pub fn item(mut self, item: SExpr) -> Self {
+        if let SExpr::List(items) = &mut self {
+            items.push(item);
+        }
+        self
+    }
+
+    /// Append several items
+    pub fn items(mut self, new_items: impl IntoIterator<Item = SExpr>) -> Self {
+        if let SExpr::List(items) = &mut self {
+            items.extend(new_items);
+        }
+        self
+    }
+
+    /// Append an unquoted token
+    pub fn sym(self, token: &str) -> Self {
+        self.item(SExpr::Symbol(token.to_string()))
+    }
+
+    /// Append a quoted string
+    pub fn str(self, value: &str) -> Self {
+        self.item(SExpr::Str(value.to_string()))
+    }
+
+    /// Append a number with up to four fraction digits
+    pub fn num(self, value: f64) -> Self {
+        self.num_prec(value, DEFAULT_DECIMALS)
+    }
+
+    /// Append a number with up to `decimals` fraction digits
+    pub fn num_prec(self, value: f64, decimals: u8) -> Self {
+        self.item(SExpr::Num(value, decimals))
+    }
+
+    /// Append an integer
+    pub fn int(self, value: i64) -> Self {
+        self.item(SExpr::Num(value as f64, 0))
+    }
+
+    /// Append `(head value)`, e.g. `(layer "F.Cu")` with a string value
+    pub fn str_field(self, head: &str, value: &str) -> Self {
+        self.item(SExpr::list(head).str(value))
+    }
+
+    /// Append `(head token)`, e.g. `(in_bom yes)`
+    pub fn sym_field(self, head: &str, token: &str) -> Self {
+        self.item(SExpr::list(head).sym(token))
+    }
+
+    /// Append `(head number)`, e.g. `(width 0.254)`
+    pub fn num_field(self, head: &str, value: f64) -> Self {
+        self.item(SExpr::list(head).num(value))
+    }
+
+    /// Append `(head yes)` or `(head no)`
+    pub fn flag(self, head: &str, value: bool) -> Self {
+        self.sym_field(head, if value { "yes" } else { "no" })
+    }
+
+    /// Render with `indent` levels of two-space indentation before the first
+    /// line; nested lists that do not fit on one line are broken up
+    pub fn render(&self, indent: usize) -> String {
+        let mut out = " ".repeat(indent * 2);
+        self.write(&mut out, indent);
+        out
+    }
+
+    fn write(&self, out: &mut String, indent: usize) {
+        let SExpr::List(items) = self else {
+            out.push_str(&self.flat());
+            return;
+        };
+
+        let flat = self.flat();
+        if indent * 2 + flat.len() <= LINE_WIDTH {
+            out.push_str(&flat);
+            return;
+        }
+
+        // Leading atoms stay on the opening line, the rest go one per line
+        let atoms = items
+            .iter()
+            .take_while(|item| !matches!(item, SExpr::List(_)))
+            .count();
+        out.push('(');
+        let head: Vec<String> = items[..atoms].iter().map(SExpr::flat).collect();
+        out.push_str(&head.join(" "));
+        for item in &items[atoms..] {
+            out.push('\n');
+            out.push_str(&" ".repeat((indent + 1) * 2));
+            item.write(out, indent + 1);
+        }
+        out.push('\n');
+        out.push_str(&" ".repeat(indent * 2));
+        out.push(')');
+    }
+
+    /// Single-line form
+    fn flat(&self) -> String {
+        match self {
+            SExpr::Symbol(token) => token.clone(),
+            SExpr::Str(value) => quote(value),
+            SExpr::Num(value, decimals) => format_number(*value, *decimals),
+            SExpr::List(items) => {
+                let items: Vec<String> = items.iter().map(SExpr::flat).collect();
+                format!("({})", items.join(" "))
+            }
+        }
+    }
+}
+
+impl fmt::Display for SExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render(0))
+    }
+}
+
+/// Quote and escape a string the way KiCad's lexer reads it back
+fn quote(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => {}
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Fixed precision without trailing zeros; never `-0` and never exponents
+fn format_number(value: f64, decimals: u8) -> String {
+    if !value.is_finite() {
+        return "0".to_string();
+    }
+    let mut text = format!("{:.*}", decimals as usize, value);
+    if text.contains('.') {
+        let trimmed = text.trim_end_matches('0').trim_end_matches('.').len();
+        text.truncate(trimmed);
+    }
+    if text == "-0" {
+        text = "0".to_string();
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_strings_and_formats_numbers() {
+        let expr = SExpr::list("property")
+            .str("Description")
+            .str("5\" \\ \"panel\"\nmount")
+            .item(SExpr::point("at", 1.0 / 3.0, -0.00001))
+            .num_prec(2.5, 0)
+            .int(-3);
+
+        assert_eq!(
+            expr.render(0),
+            r#"(property "Description" "5\" \\ \"panel\"\nmount" (at 0.3333 0) 2 -3)"#
+        );
+        assert_eq!(format_number(1.2700000000000002, 4), "1.27");
+        assert_eq!(format_number(1e-12, 4), "0");
+        assert_eq!(format_number(f64::NAN, 4), "0");
+    }
+
+    #[test]
+    fn breaks_long_lists() {
+        let pts = SExpr::list("pts").items((0..10).map(|i| SExpr::point("xy", i as f64, 12.5)));
+        let expr = SExpr::list("polyline")
+            .item(pts)
+            .item(SExpr::list("stroke").num_field("width", 0.254));
+
+        let rendered = expr.render(1);
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines[0], "  (polyline");
+        assert_eq!(lines[1], "    (pts");
+        assert_eq!(lines[2], "      (xy 0 12.5)");
+        assert_eq!(lines[11], "      (xy 9 12.5)");
+        assert_eq!(lines[12], "    )");
+        assert_eq!(lines[13], "    (stroke (width 0.254))");
+        assert_eq!(lines[14], "  )");
+    }
+}
@@ -113,16 +113,37 @@ pub struct KiSymbol {
     pub reference: String,
     pub value: String,
     pub description: String,
+    /// Space-separated search keywords
+    pub keywords: String,
     pub footprint: String,
     pub datasheet: String,
     pub manufacturer: String,
     pub lcsc_id: String,
     pub jlc_id: String,
+    /// Extra hidden properties as (name, value), e.g. mapped from EasyEDA `c_para`
+    pub properties: Vec<(String, String)>,
+    /// Base symbol in the same library; derived symbols carry only properties
+    pub extends: Option<String>,
     pub pins: Vec<KiPin>,
     pub rectangles: Vec<KiRectangle>,
     pub circles: Vec<KiCircle>,
     pub arcs: Vec<KiArc>,
     pub polylines: Vec<KiPolyline>,
+    pub beziers: Vec<KiBezier>,
+    pub texts: Vec<KiText>,
+    /// Units of a multi-unit symbol, exported as `<name>_N_1`; the fields above
+    /// are common to all units (`<name>_0_1`)
+    pub units: Vec<KiSymbolUnit>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct KiSymbolUnit {
+    pub pins: Vec<KiPin>,
+    pub rectangles: Vec<KiRectangle>,
+    pub circles: Vec<KiCircle>,
+    pub arcs: Vec<KiArc>,
+    pub polylines: Vec<KiPolyline>,
+    pub beziers: Vec<KiBezier>,
     pub texts: Vec<KiText>,
 }
 
@@ -145,6 +166,8 @@ pub struct KiPin {
     pub pos_y: f64,
     pub rotation: i32,
     pub length: f64,
+    pub name_visible: bool,
+    pub number_visible: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -183,3 +206,11 @@ pub struct KiPolyline {
     pub stroke_width: f64,
     pub fill: bool,
 }
+
+/// Cubic Bezier: start, two control points, end
+#[derive(Debug, Clone)]
+pub struct KiBezier {
+    pub points: [(f64, f64); 4],
+    pub stroke_width: f64,
+    pub fill: bool,
+}
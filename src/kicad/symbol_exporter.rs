@@ -1,6 +1,7 @@
 use crate::cli::KicadVersion;
-use crate::converter::Converter;
+use crate::converter::{Converter, arc_through};
 use crate::error::Result;
+use crate::kicad::sexpr::SExpr;
 use crate::kicad::symbol::*;
 
 pub struct SymbolExporter {
@@ -18,169 +19,215 @@ impl SymbolExporter {
 
     pub fn export(&self, symbol: &KiSymbol) -> Result<String> {
         match self.version {
-            KicadVersion::V6 => self.export_v6(symbol),
             KicadVersion::V5 => self.export_v5(symbol),
+            KicadVersion::V6 | KicadVersion::V7 | KicadVersion::V8 | KicadVersion::V9 => {
+                self.export_v6(symbol)
+            }
         }
     }
 
     fn export_v6(&self, symbol: &KiSymbol) -> Result<String> {
-        let mut output = String::new();
-
         // Calculate y_high and y_low from pin positions
         let (y_high, y_low) = self.calculate_y_bounds(symbol);
 
-        // Start symbol definition - match Python formatting
-        output.push_str(&format!("  (symbol \"{}\"\n", symbol.name));
-        output.push_str("    (in_bom yes)\n");
-        output.push_str("    (on_board yes)\n");
+        let mut sexpr = SExpr::list("symbol").str(&symbol.name);
+        if let Some(base) = &symbol.extends {
+            sexpr = sexpr.str_field("extends", base);
+        } else {
+            // KiCad can only hide pin names/numbers for the whole symbol
+            let (show_names, show_numbers) = self.pin_text_visibility(symbol);
+            let hide = |head: &str| {
+                if self.version >= KicadVersion::V9 {
+                    SExpr::list(head).flag("hide", true)
+                } else {
+                    SExpr::list(head).sym("hide")
+                }
+            };
+            if !show_numbers {
+                sexpr = sexpr.item(hide("pin_numbers"));
+            }
+            if !show_names {
+                sexpr = sexpr.item(hide("pin_names"));
+            }
+            if self.version >= KicadVersion::V8 {
+                sexpr = sexpr.flag("exclude_from_sim", false);
+            }
+            sexpr = sexpr.flag("in_bom", true).flag("on_board", true);
+        }
 
         // Properties with proper formatting
         const FIELD_OFFSET_START: f64 = 5.08;
         const FIELD_OFFSET_INCREMENT: f64 = 2.54;
-        let mut field_offset_y = FIELD_OFFSET_START;
-        let mut property_id = 0;
-
-        // Reference property
-        output.push_str("    (property\n");
-        output.push_str("      \"Reference\"\n");
-        output.push_str(&format!("      \"{}\"\n", symbol.reference));
-        output.push_str(&format!("      (id {})\n", property_id));
-        output.push_str(&format!("      (at 0 {:.2} 0)\n", y_high + field_offset_y));
-        output.push_str("      (effects (font (size 1.27 1.27) ) )\n");
-        output.push_str("    )\n");
-        property_id += 1;
-
-        // Value property
-        output.push_str("    (property\n");
-        output.push_str("      \"Value\"\n");
-        output.push_str(&format!("      \"{}\"\n", symbol.value));
-        output.push_str(&format!("      (id {})\n", property_id));
-        output.push_str(&format!("      (at 0 {:.2} 0)\n", y_low - field_offset_y));
-        output.push_str("      (effects (font (size 1.27 1.27) ) )\n");
-        output.push_str("    )\n");
-        property_id += 1;
-
-        // Footprint property
-        if !symbol.footprint.is_empty() {
-            field_offset_y += FIELD_OFFSET_INCREMENT;
-            output.push_str("    (property\n");
-            output.push_str("      \"Footprint\"\n");
-            output.push_str(&format!("      \"{}\"\n", symbol.footprint));
-            output.push_str(&format!("      (id {})\n", property_id));
-            output.push_str(&format!("      (at 0 {:.2} 0)\n", y_low - field_offset_y));
-            output.push_str("      (effects (font (size 1.27 1.27) ) hide)\n");
-            output.push_str("    )\n");
-            property_id += 1;
+
+        // Reference above the body, everything else stacked below it
+        let mut fields: Vec<(&str, &str, bool)> = vec![
+            ("Value", &symbol.value, false),
+            ("Footprint", &symbol.footprint, true),
+            ("Datasheet", &symbol.datasheet, true),
+            ("Description", &symbol.description, true),
+            ("ki_keywords", &symbol.keywords, true),
+            ("Manufacturer", &symbol.manufacturer, true),
+            ("LCSC Part", &symbol.lcsc_id, true),
+            ("JLC Part", &symbol.jlc_id, true),
+        ];
+        // Datasheet and Description are always emitted, the others only when set
+        fields.retain(|(name, value, _)| {
+            !value.is_empty() || matches!(*name, "Value" | "Datasheet" | "Description")
+        });
+        // Extra properties (property names must be unique within a symbol)
+        for (name, value) in self.extra_properties(symbol) {
+            fields.push((name, value, true));
         }
 
-        // Datasheet property (always emit)
-        field_offset_y += FIELD_OFFSET_INCREMENT;
-        output.push_str("    (property\n");
-        output.push_str("      \"Datasheet\"\n");
-        output.push_str(&format!("      \"{}\"\n", symbol.datasheet));
-        output.push_str(&format!("      (id {})\n", property_id));
-        output.push_str(&format!("      (at 0 {:.2} 0)\n", y_low - field_offset_y));
-        output.push_str("      (effects (font (size 1.27 1.27) ) hide)\n");
-        output.push_str("    )\n");
-        property_id += 1;
-
-        // Description property (always emit)
-        field_offset_y += FIELD_OFFSET_INCREMENT;
-        output.push_str("    (property\n");
-        output.push_str("      \"Description\"\n");
-        output.push_str(&format!("      \"{}\"\n", symbol.description));
-        output.push_str(&format!("      (id {})\n", property_id));
-        output.push_str(&format!("      (at 0 {:.2} 0)\n", y_low - field_offset_y));
-        output.push_str("      (effects (font (size 1.27 1.27) ) hide)\n");
-        output.push_str("    )\n");
-        property_id += 1;
-
-        // Manufacturer property
-        if !symbol.manufacturer.is_empty() {
-            field_offset_y += FIELD_OFFSET_INCREMENT;
-            output.push_str("    (property\n");
-            output.push_str("      \"Manufacturer\"\n");
-            output.push_str(&format!("      \"{}\"\n", symbol.manufacturer));
-            output.push_str(&format!("      (id {})\n", property_id));
-            output.push_str(&format!("      (at 0 {:.2} 0)\n", y_low - field_offset_y));
-            output.push_str("      (effects (font (size 1.27 1.27) ) hide)\n");
-            output.push_str("    )\n");
-            property_id += 1;
+        sexpr = sexpr.item(self.format_property_v6(
+            "Reference",
+            &symbol.reference,
+            0,
+            y_high + FIELD_OFFSET_START,
+            false,
+        ));
+        for (i, (name, value, hidden)) in fields.into_iter().enumerate() {
+            let y = y_low - FIELD_OFFSET_START - i as f64 * FIELD_OFFSET_INCREMENT;
+            sexpr = sexpr.item(self.format_property_v6(name, value, i + 1, y, hidden));
         }
 
-        // LCSC Part property
-        if !symbol.lcsc_id.is_empty() {
-            field_offset_y += FIELD_OFFSET_INCREMENT;
-            output.push_str("    (property\n");
-            output.push_str("      \"LCSC Part\"\n");
-            output.push_str(&format!("      \"{}\"\n", symbol.lcsc_id));
-            output.push_str(&format!("      (id {})\n", property_id));
-            output.push_str(&format!("      (at 0 {:.2} 0)\n", y_low - field_offset_y));
-            output.push_str("      (effects (font (size 1.27 1.27) ) hide)\n");
-            output.push_str("    )\n");
-            property_id += 1;
+        // Derived symbols take their graphics and pins from the base symbol
+        if symbol.extends.is_none() {
+            // Symbol graphics section (unit 0, convert 1) - contains body graphics
+            // and, for multi-unit symbols, the pins shared by every unit
+            let common = self.common_unit(symbol);
+            if symbol.units.is_empty() || !self.is_empty_unit(&common) {
+                sexpr = sexpr.item(self.format_unit_v6(&symbol.name, 0, &common));
+            }
+
+            // One section per unit of a multi-unit symbol
+            for (i, unit) in symbol.units.iter().enumerate() {
+                sexpr = sexpr.item(self.format_unit_v6(&symbol.name, i + 1, unit));
+            }
         }
 
-        // JLC Part property
-        if !symbol.jlc_id.is_empty() {
-            field_offset_y += FIELD_OFFSET_INCREMENT;
-            output.push_str("    (property\n");
-            output.push_str("      \"JLC Part\"\n");
-            output.push_str(&format!("      \"{}\"\n", symbol.jlc_id));
-            output.push_str(&format!("      (id {})\n", property_id));
-            output.push_str(&format!("      (at 0 {:.2} 0)\n", y_low - field_offset_y));
-            output.push_str("      (effects (font (size 1.27 1.27) ) hide)\n");
-            output.push_str("    )\n");
+        // KiCad 9 records whether fonts are embedded
+        if self.version >= KicadVersion::V9 {
+            sexpr = sexpr.flag("embedded_fonts", false);
         }
 
-        // Symbol graphics section (unit 0, convert 1) - contains body graphics
-        output.push_str(&format!("    (symbol \"{}_0_1\"\n", symbol.name));
+        // Symbols sit one level deep inside the kicad_symbol_lib list
+        Ok(format!("{}\n", sexpr.render(1)))
+    }
 
-        // Rectangles
-        for rect in &symbol.rectangles {
-            output.push_str(&self.format_rectangle_v6(rect));
+    fn format_property_v6(
+        &self,
+        name: &str,
+        value: &str,
+        id: usize,
+        y: f64,
+        hidden: bool,
+    ) -> SExpr {
+        let mut property = SExpr::list("property").str(name).str(value);
+        // KiCad 8 dropped property ids
+        if self.version < KicadVersion::V8 {
+            property = property.item(SExpr::list("id").int(id as i64));
         }
+        let effects = match (hidden, self.version >= KicadVersion::V8) {
+            (false, _) => font_effects(1.27),
+            (true, false) => font_effects(1.27).sym("hide"),
+            (true, true) => font_effects(1.27).flag("hide", true),
+        };
+        property.item(at(0.0, y, 0.0)).item(effects)
+    }
 
-        // Circles
-        for circle in &symbol.circles {
-            output.push_str(&self.format_circle_v6(circle));
-        }
+    fn format_unit_v6(&self, name: &str, unit_number: usize, unit: &KiSymbolUnit) -> SExpr {
+        SExpr::list("symbol")
+            .str(&format!("{}_{}_1", name, unit_number))
+            .items(unit.rectangles.iter().map(|r| self.format_rectangle_v6(r)))
+            .items(unit.circles.iter().map(|c| self.format_circle_v6(c)))
+            .items(unit.arcs.iter().map(|a| self.format_arc_v6(a)))
+            .items(unit.polylines.iter().map(|p| self.format_polyline_v6(p)))
+            .items(unit.beziers.iter().map(|b| self.format_bezier_v6(b)))
+            .items(unit.texts.iter().map(|t| self.format_text_v6(t)))
+            // Pins - in the same section as graphics
+            .items(unit.pins.iter().map(|p| self.format_pin_v6(p)))
+    }
 
-        // Arcs
-        for arc in &symbol.arcs {
-            output.push_str(&self.format_arc_v6(arc));
+    /// Whether any pin shows its name and its number; symbols without pins show both
+    fn pin_text_visibility(&self, symbol: &KiSymbol) -> (bool, bool) {
+        let mut pins = symbol
+            .pins
+            .iter()
+            .chain(symbol.units.iter().flat_map(|u| u.pins.iter()))
+            .peekable();
+        if pins.peek().is_none() {
+            return (true, true);
         }
 
-        // Polylines
-        for polyline in &symbol.polylines {
-            output.push_str(&self.format_polyline_v6(polyline));
-        }
+        pins.fold((false, false), |(names, numbers), pin| {
+            (names || pin.name_visible, numbers || pin.number_visible)
+        })
+    }
 
-        // Texts
-        for text in &symbol.texts {
-            output.push_str(&self.format_text_v6(text));
+    /// `symbol.properties` minus names already used by the standard fields
+    fn extra_properties<'a>(&self, symbol: &'a KiSymbol) -> Vec<(&'a str, &'a str)> {
+        const STANDARD_FIELDS: &[&str] = &[
+            "Reference",
+            "Value",
+            "Footprint",
+            "Datasheet",
+            "Description",
+            "ki_keywords",
+            "Manufacturer",
+            "LCSC Part",
+            "JLC Part",
+        ];
+
+        let mut properties: Vec<(&str, &str)> = Vec::new();
+        for (name, value) in &symbol.properties {
+            if STANDARD_FIELDS.contains(&name.as_str()) || properties.iter().any(|(n, _)| n == name)
+            {
+                log::debug!("Skipping duplicate symbol property {}", name);
+                continue;
+            }
+            properties.push((name, value));
         }
+        properties
+    }
 
-        // Pins - in the same _0_1 section as graphics
-        for pin in &symbol.pins {
-            output.push_str(&self.format_pin_v6(pin));
+    /// Items drawn outside `symbol.units`, common to all units
+    fn common_unit(&self, symbol: &KiSymbol) -> KiSymbolUnit {
+        KiSymbolUnit {
+            pins: symbol.pins.clone(),
+            rectangles: symbol.rectangles.clone(),
+            circles: symbol.circles.clone(),
+            arcs: symbol.arcs.clone(),
+            polylines: symbol.polylines.clone(),
+            beziers: symbol.beziers.clone(),
+            texts: symbol.texts.clone(),
         }
+    }
 
-        output.push_str("    )\n");
-        output.push_str("  )\n");
-
-        Ok(output)
+    fn is_empty_unit(&self, unit: &KiSymbolUnit) -> bool {
+        unit.pins.is_empty()
+            && unit.rectangles.is_empty()
+            && unit.circles.is_empty()
+            && unit.arcs.is_empty()
+            && unit.polylines.is_empty()
+            && unit.beziers.is_empty()
+            && unit.texts.is_empty()
     }
 
     fn calculate_y_bounds(&self, symbol: &KiSymbol) -> (f64, f64) {
-        if symbol.pins.is_empty() {
+        let pins: Vec<&KiPin> = symbol
+            .pins
+            .iter()
+            .chain(symbol.units.iter().flat_map(|u| u.pins.iter()))
+            .collect();
+        if pins.is_empty() {
             return (0.0, 0.0);
         }
 
         let mut y_high = f64::MIN;
         let mut y_low = f64::MAX;
 
-        for pin in &symbol.pins {
+        for pin in pins {
             let y = self.converter.px_to_mm(pin.pos_y);
             if y > y_high {
                 y_high = y;
@@ -193,51 +240,131 @@ impl SymbolExporter {
         (y_high, y_low)
     }
 
+    /// `.dcm` entry with the description, keywords and datasheet of a KiCad 5 symbol
+    pub fn export_doc(&self, symbol: &KiSymbol) -> String {
+        let mut output = format!("$CMP {}\n", symbol.name);
+        let one_line = |s: &str| s.split_whitespace().collect::<Vec<_>>().join(" ");
+        if !symbol.description.trim().is_empty() {
+            output.push_str(&format!("D {}\n", one_line(&symbol.description)));
+        }
+        if !symbol.keywords.trim().is_empty() {
+            output.push_str(&format!("K {}\n", one_line(&symbol.keywords)));
+        }
+        if !symbol.datasheet.trim().is_empty() && symbol.datasheet != "~" {
+            output.push_str(&format!("F {}\n", symbol.datasheet.trim()));
+        }
+        output.push_str("$ENDCMP\n");
+        output
+    }
+
     fn export_v5(&self, symbol: &KiSymbol) -> Result<String> {
         let mut output = String::new();
+        let unit_count = symbol.units.len().max(1);
+
+        let (show_names, show_numbers) = self.pin_text_visibility(symbol);
+        let flag = |shown: bool| if shown { "Y" } else { "N" };
 
         // DEF name reference unused text_offset draw_pinnumber draw_pinname unit_count units_locked option_flag
         output.push_str(&format!(
-            "DEF {} {} 0 40 Y Y 1 F N\n",
-            symbol.name, symbol.reference
+            "DEF {} {} 0 40 {} {} {} {} N\n",
+            symbol.name,
+            symbol.reference,
+            flag(show_numbers),
+            flag(show_names),
+            unit_count,
+            if unit_count > 1 { "L" } else { "F" }
         ));
 
         // F0 reference x y size orientation visibility hjustify vjustify/italic/bold
-        output.push_str(&format!("F0 \"{}\" 0 0 50 H V C CNN\n", symbol.reference));
-        output.push_str(&format!("F1 \"{}\" 0 -100 50 H V C CNN\n", symbol.value));
-        output.push_str(&format!("F2 \"{}\" 0 0 50 H I C CNN\n", symbol.footprint));
-        output.push_str(&format!("F3 \"{}\" 0 0 50 H I C CNN\n", symbol.datasheet));
+        output.push_str(&format!(
+            "F0 \"{}\" 0 0 50 H V C CNN\n",
+            escape_string(&symbol.reference)
+        ));
+        output.push_str(&format!(
+            "F1 \"{}\" 0 -100 50 H V C CNN\n",
+            escape_string(&symbol.value)
+        ));
+        output.push_str(&format!(
+            "F2 \"{}\" 0 0 50 H I C CNN\n",
+            escape_string(&symbol.footprint)
+        ));
+        output.push_str(&format!(
+            "F3 \"{}\" 0 0 50 H I C CNN\n",
+            escape_string(&symbol.datasheet)
+        ));
+        // Named fields; the description and keywords go into the .dcm file
+        let mut fields: Vec<(&str, &str)> = [
+            ("Manufacturer", symbol.manufacturer.as_str()),
+            ("LCSC Part", symbol.lcsc_id.as_str()),
+            ("JLC Part", symbol.jlc_id.as_str()),
+        ]
+        .into_iter()
+        .filter(|(_, value)| !value.is_empty())
+        .collect();
+        fields.extend(self.extra_properties(symbol));
+        for (i, (name, value)) in fields.into_iter().enumerate() {
+            output.push_str(&format!(
+                "F{} \"{}\" 0 0 50 H I C CNN \"{}\"\n",
+                i + 4,
+                escape_string(value),
+                escape_string(name)
+            ));
+        }
 
         // DRAW
         output.push_str("DRAW\n");
 
+        // Unit 0 is common to all units; single-unit symbols draw everything as unit 1
+        let common_unit = if symbol.units.is_empty() { 1 } else { 0 };
+        self.format_unit_v5(&mut output, common_unit, &self.common_unit(symbol));
+        for (i, unit) in symbol.units.iter().enumerate() {
+            self.format_unit_v5(&mut output, i + 1, unit);
+        }
+
+        output.push_str("ENDDRAW\n");
+        output.push_str("ENDDEF\n");
+
+        Ok(output)
+    }
+
+    fn format_unit_v5(&self, output: &mut String, unit: usize, items: &KiSymbolUnit) {
         // Rectangles
-        for rect in &symbol.rectangles {
-            output.push_str(&self.format_rectangle_v5(rect));
+        for rect in &items.rectangles {
+            output.push_str(&self.format_rectangle_v5(rect, unit));
         }
 
         // Circles
-        for circle in &symbol.circles {
-            output.push_str(&self.format_circle_v5(circle));
+        for circle in &items.circles {
+            output.push_str(&self.format_circle_v5(circle, unit));
+        }
+
+        // Arcs
+        for arc in &items.arcs {
+            output.push_str(&self.format_arc_v5(arc, unit));
         }
 
         // Polylines
-        for polyline in &symbol.polylines {
-            output.push_str(&self.format_polyline_v5(polyline));
+        for polyline in &items.polylines {
+            output.push_str(&self.format_polyline_v5(polyline, unit));
         }
 
-        // Pins
-        for pin in &symbol.pins {
-            output.push_str(&self.format_pin_v5(pin));
+        // Beziers
+        for bezier in &items.beziers {
+            output.push_str(&self.format_bezier_v5(bezier, unit));
         }
 
-        output.push_str("ENDDRAW\n");
-        output.push_str("ENDDEF\n");
+        // Texts
+        for text in &items.texts {
+            output.push_str(&self.format_text_v5(text, unit));
+        }
 
-        Ok(output)
+        // Pins
+        for pin in &items.pins {
+            output.push_str(&self.format_pin_v5(pin, unit));
+        }
     }
 
-    fn format_pin_v6(&self, pin: &KiPin) -> String {
+    fn format_pin_v6(&self, pin: &KiPin) -> SExpr {
         let x = self.converter.px_to_mm(pin.pos_x);
         let y = self.converter.px_to_mm(pin.pos_y);
         let length = self.converter.px_to_mm(pin.length);
@@ -245,61 +372,68 @@ impl SymbolExporter {
         // Convert pin rotation: (180 + orientation) % 360
         let orientation = (180 + pin.rotation) % 360;
 
-        format!(
-            "      (pin {} {}\n        (at {:.2} {:.2} {})\n        (length {:.2})\n        (name \"{}\" (effects (font (size 1.27 1.27))))\n        (number \"{}\" (effects (font (size 1.27 1.27))))\n      )\n",
-            pin.pin_type.to_kicad_v6(),
-            pin.style.to_kicad_v6(),
-            x,
-            y,
-            orientation,
-            length,
-            pin.name,
-            pin.number
-        )
+        SExpr::list("pin")
+            .sym(pin.pin_type.to_kicad_v6())
+            .sym(pin.style.to_kicad_v6())
+            .item(at(x, y, orientation as f64))
+            .num_field("length", length)
+            .item(SExpr::list("name").str(&pin.name).item(font_effects(1.27)))
+            .item(
+                SExpr::list("number")
+                    .str(&pin.number)
+                    .item(font_effects(1.27)),
+            )
     }
 
-    fn format_pin_v5(&self, pin: &KiPin) -> String {
+    fn format_pin_v5(&self, pin: &KiPin, unit: usize) -> String {
         let x = self.converter.px_to_mil(pin.pos_x);
         let y = self.converter.px_to_mil(pin.pos_y); // Don't flip, already handled
         let length = self.converter.px_to_mil(pin.length);
 
+        // Same orientation as the v6 pin: (180 + orientation) % 360
+        let orientation = (180 + pin.rotation) % 360;
+
         // X name number posx posy length orientation Snum Snom unit convert Etype [shape]
-        format!(
-            "X {} {} {} {} {} {} {} {} {} {} {}\n",
-            pin.name,
-            pin.number,
+        let mut output = format!(
+            "X {} {} {} {} {} {} {} {} {} {} {}",
+            v5_token(&pin.name),
+            v5_token(&pin.number),
             x,
             y,
             length,
-            self.rotation_to_direction(pin.rotation),
+            self.rotation_to_direction(orientation),
             50, // name size
             50, // number size
-            1,  // unit
-            1,  // convert
+            unit,
+            1, // convert
             pin.pin_type.to_kicad_v5()
-        )
+        );
+        let shape = pin.style.to_kicad_v5();
+        if !shape.is_empty() {
+            output.push(' ');
+            output.push_str(shape);
+        }
+        output.push('\n');
+        output
     }
 
-    fn format_rectangle_v6(&self, rect: &KiRectangle) -> String {
-        let x1 = self.converter.px_to_mm(rect.x1);
-        let y1 = self.converter.px_to_mm(rect.y1);
-        let x2 = self.converter.px_to_mm(rect.x2);
-        let y2 = self.converter.px_to_mm(rect.y2);
-        let _width = self.converter.px_to_mm(rect.stroke_width);
-
+    fn format_rectangle_v6(&self, rect: &KiRectangle) -> SExpr {
         let fill = if rect.fill {
-            "(fill (type color) (color 255 192 203 1))"
+            SExpr::list("fill")
+                .sym_field("type", "color")
+                .item(SExpr::list("color").int(255).int(192).int(203).int(1))
         } else {
-            "(fill (type none))"
+            fill("none")
         };
 
-        format!(
-            "      (rectangle\n        (start {:.2} {:.2})\n        (end {:.2} {:.2})\n        (stroke (width {}) (type default) (color 0 0 0 0))\n        {}\n      )\n",
-            x1, y1, x2, y2, 0, fill
-        )
+        SExpr::list("rectangle")
+            .item(self.point("start", rect.x1, rect.y1))
+            .item(self.point("end", rect.x2, rect.y2))
+            .item(stroke(0.0).item(SExpr::list("color").int(0).int(0).int(0).int(0)))
+            .item(fill)
     }
 
-    fn format_rectangle_v5(&self, rect: &KiRectangle) -> String {
+    fn format_rectangle_v5(&self, rect: &KiRectangle, unit: usize) -> String {
         let x1 = self.converter.px_to_mil(rect.x1);
         let y1 = self.converter.px_to_mil(rect.y1); // Don't flip, already handled
         let x2 = self.converter.px_to_mil(rect.x2);
@@ -308,24 +442,19 @@ impl SymbolExporter {
         let fill = if rect.fill { "F" } else { "N" };
 
         // S startx starty endx endy unit convert thickness fill
-        format!("S {} {} {} {} 1 1 10 {}\n", x1, y1, x2, y2, fill)
+        format!("S {} {} {} {} {} 1 10 {}\n", x1, y1, x2, y2, unit, fill)
     }
 
-    fn format_circle_v6(&self, circle: &KiCircle) -> String {
-        let cx = self.converter.px_to_mm(circle.cx);
-        let cy = self.converter.px_to_mm(circle.cy);
-        let radius = self.converter.px_to_mm(circle.radius);
-
+    fn format_circle_v6(&self, circle: &KiCircle) -> SExpr {
         // Circles in symbols should always have fill type "none" to match Python output
-        let fill = "none";
-
-        format!(
-            "      (circle\n        (center {:.2} {:.2})\n        (radius {:.2})\n        (stroke (width {}) (type default) (color 0 0 0 0))\n        (fill (type {}))\n      )\n",
-            cx, cy, radius, 0, fill
-        )
+        SExpr::list("circle")
+            .item(self.point("center", circle.cx, circle.cy))
+            .num_field("radius", self.converter.px_to_mm(circle.radius))
+            .item(stroke(0.0).item(SExpr::list("color").int(0).int(0).int(0).int(0)))
+            .item(fill("none"))
     }
 
-    fn format_circle_v5(&self, circle: &KiCircle) -> String {
+    fn format_circle_v5(&self, circle: &KiCircle, unit: usize) -> String {
         let cx = self.converter.px_to_mil(circle.cx);
         let cy = self.converter.px_to_mil(circle.cy); // Don't flip, already handled
         let radius = self.converter.px_to_mil(circle.radius);
@@ -333,58 +462,109 @@ impl SymbolExporter {
         let fill = if circle.fill { "F" } else { "N" };
 
         // C posx posy radius unit convert thickness fill
-        format!("C {} {} {} 1 1 10 {}\n", cx, cy, radius, fill)
+        format!("C {} {} {} {} 1 10 {}\n", cx, cy, radius, unit, fill)
     }
 
-    fn format_arc_v6(&self, arc: &KiArc) -> String {
-        let start_x = self.converter.px_to_mm(arc.start_x);
-        let start_y = self.converter.px_to_mm(arc.start_y); // Don't flip, already handled
-        let mid_x = self.converter.px_to_mm(arc.mid_x);
-        let mid_y = self.converter.px_to_mm(arc.mid_y); // Don't flip, already handled
-        let end_x = self.converter.px_to_mm(arc.end_x);
-        let end_y = self.converter.px_to_mm(arc.end_y); // Don't flip, already handled
-        let width = self.converter.px_to_mm(arc.stroke_width);
+    fn format_arc_v6(&self, arc: &KiArc) -> SExpr {
+        SExpr::list("arc")
+            .item(self.point("start", arc.start_x, arc.start_y))
+            .item(self.point("mid", arc.mid_x, arc.mid_y))
+            .item(self.point("end", arc.end_x, arc.end_y))
+            .item(stroke(self.converter.px_to_mm(arc.stroke_width)))
+            .item(fill("none"))
+    }
 
+    fn format_arc_v5(&self, arc: &KiArc, unit: usize) -> String {
+        let start = (arc.start_x, arc.start_y);
+        let end = (arc.end_x, arc.end_y);
+        let Some((center, start_angle, sweep)) = arc_through(start, (arc.mid_x, arc.mid_y), end)
+        else {
+            // Degenerate arc: draw the chord
+            return self.format_polyline_v5(
+                &KiPolyline {
+                    points: vec![start, end],
+                    stroke_width: arc.stroke_width,
+                    fill: false,
+                },
+                unit,
+            );
+        };
+
+        // KiCad 5 draws arcs counter-clockwise from the first to the second angle
+        let (first, second, angle) = if sweep >= 0.0 {
+            (start, end, start_angle)
+        } else {
+            (end, start, start_angle + sweep)
+        };
+        let tenths = |deg: f64| {
+            let t = (deg * 10.0).round() as i32;
+            // Normalize to (-1800, 1800]
+            let t = t.rem_euclid(3600);
+            if t > 1800 { t - 3600 } else { t }
+        };
+        let radius = (first.0 - center.0).hypot(first.1 - center.1);
+
+        // A posx posy radius start_angle end_angle unit convert thickness fill startx starty endx endy
         format!(
-            "    (arc (start {:.4} {:.4}) (mid {:.4} {:.4}) (end {:.4} {:.4})\n      (stroke (width {:.4}) (type default))\n      (fill (type none))\n    )\n",
-            start_x, start_y, mid_x, mid_y, end_x, end_y, width
+            "A {} {} {} {} {} {} 1 {} N {} {} {} {}\n",
+            self.converter.px_to_mil(center.0),
+            self.converter.px_to_mil(center.1),
+            self.converter.px_to_mil(radius),
+            tenths(angle),
+            tenths(angle + sweep.abs()),
+            unit,
+            self.converter.px_to_mil(arc.stroke_width),
+            self.converter.px_to_mil(first.0),
+            self.converter.px_to_mil(first.1),
+            self.converter.px_to_mil(second.0),
+            self.converter.px_to_mil(second.1)
         )
     }
 
-    fn format_polyline_v6(&self, polyline: &KiPolyline) -> String {
-        let mut output = String::from("    (polyline\n      (pts\n");
+    fn format_polyline_v6(&self, polyline: &KiPolyline) -> SExpr {
+        let pts = polyline.points.iter().map(|&(x, y)| self.point("xy", x, y));
+
+        SExpr::list("polyline")
+            .item(SExpr::list("pts").items(pts))
+            .item(stroke(self.converter.px_to_mm(polyline.stroke_width)))
+            .item(fill(if polyline.fill { "outline" } else { "none" }))
+    }
+
+    fn format_polyline_v5(&self, polyline: &KiPolyline, unit: usize) -> String {
+        let point_count = polyline.points.len();
+        let mut output = format!("P {} {} 1 10", point_count, unit);
 
         for (x, y) in &polyline.points {
-            let x = self.converter.px_to_mm(*x);
-            let y = self.converter.px_to_mm(*y); // Don't flip, already handled
-            output.push_str(&format!("        (xy {:.4} {:.4})\n", x, y));
+            let x = self.converter.px_to_mil(*x);
+            let y = self.converter.px_to_mil(*y); // Don't flip, already handled
+            output.push_str(&format!(" {} {}", x, y));
         }
 
-        let width = self.converter.px_to_mm(polyline.stroke_width);
-        let fill = if polyline.fill { "outline" } else { "none" };
-
-        output.push_str("      )\n");
-        output.push_str(&format!(
-            "      (stroke (width {:.4}) (type default))\n",
-            width
-        ));
-        output.push_str(&format!("      (fill (type {}))\n", fill));
-        output.push_str("    )\n");
+        let fill = if polyline.fill { "F" } else { "N" };
+        output.push_str(&format!(" {}\n", fill));
 
         output
     }
 
-    fn format_polyline_v5(&self, polyline: &KiPolyline) -> String {
-        let point_count = polyline.points.len();
-        let mut output = format!("P {} 1 1 10", point_count);
+    fn format_bezier_v6(&self, bezier: &KiBezier) -> SExpr {
+        let pts = bezier.points.iter().map(|&(x, y)| self.point("xy", x, y));
 
-        for (x, y) in &polyline.points {
+        SExpr::list("bezier")
+            .item(SExpr::list("pts").items(pts))
+            .item(stroke(self.converter.px_to_mm(bezier.stroke_width)))
+            .item(fill(if bezier.fill { "outline" } else { "none" }))
+    }
+
+    fn format_bezier_v5(&self, bezier: &KiBezier, unit: usize) -> String {
+        let mut output = format!("B 4 {} 1 10", unit);
+
+        for (x, y) in &bezier.points {
             let x = self.converter.px_to_mil(*x);
-            let y = self.converter.px_to_mil(*y); // Don't flip, already handled
+            let y = self.converter.px_to_mil(*y);
             output.push_str(&format!(" {} {}", x, y));
         }
 
-        let fill = if polyline.fill { "F" } else { "N" };
+        let fill = if bezier.fill { "F" } else { "N" };
         output.push_str(&format!(" {}\n", fill));
 
         output
@@ -400,15 +580,74 @@ impl SymbolExporter {
         }
     }
 
-    fn format_text_v6(&self, text: &super::symbol::KiText) -> String {
-        let x = self.converter.px_to_mm(text.x);
-        let y = self.converter.px_to_mm(text.y);
-        let size = (text.font_size * 0.15).max(0.5).min(1.27);
-        let rotation = text.rotation;
+    fn format_text_v5(&self, text: &super::symbol::KiText, unit: usize) -> String {
+        let x = self.converter.px_to_mil(text.x);
+        let y = self.converter.px_to_mil(text.y);
+        // Same size as the v6 text, in mil
+        let size = ((text.font_size * 0.15).clamp(0.5, 1.27) / 0.0254).round() as i32;
+        let angle = (text.rotation * 10.0).round() as i32;
 
+        // T angle posx posy size hidden unit convert text italic bold hjustify vjustify
         format!(
-            "    (text \"{}\" (at {:.4} {:.4} {})\n      (effects (font (size {:.4} {:.4})))\n    )\n",
-            text.text, x, y, rotation as i32, size, size
+            "T {} {} {} {} 0 {} 1 \"{}\" Normal 0 C C\n",
+            angle,
+            x,
+            y,
+            size,
+            unit,
+            escape_string(&text.text)
         )
     }
+
+    fn format_text_v6(&self, text: &super::symbol::KiText) -> SExpr {
+        let x = self.converter.px_to_mm(text.x);
+        let y = self.converter.px_to_mm(text.y);
+        let size = (text.font_size * 0.15).clamp(0.5, 1.27);
+
+        SExpr::list("text")
+            .str(&text.text)
+            .item(at(x, y, text.rotation.trunc()))
+            .item(font_effects(size))
+    }
+
+    /// `(head x y)` with a px position converted to mm
+    fn point(&self, head: &str, x: f64, y: f64) -> SExpr {
+        SExpr::point(head, self.converter.px_to_mm(x), self.converter.px_to_mm(y))
+    }
+}
+
+/// Pin names and numbers are space-separated tokens in KiCad 5; `~` is empty
+fn v5_token(s: &str) -> String {
+    if s.trim().is_empty() {
+        "~".to_string()
+    } else {
+        s.replace(char::is_whitespace, "_")
+    }
+}
+
+/// `(at x y angle)`
+fn at(x: f64, y: f64, angle: f64) -> SExpr {
+    SExpr::point("at", x, y).num(angle)
+}
+
+/// `(effects (font (size s s)))`
+fn font_effects(size: f64) -> SExpr {
+    SExpr::list("effects").item(SExpr::list("font").item(SExpr::point("size", size, size)))
+}
+
+/// `(stroke (width w) (type default))`
+fn stroke(width: f64) -> SExpr {
+    SExpr::list("stroke")
+        .num_field("width", width)
+        .sym_field("type", "default")
+}
+
+/// `(fill (type kind))`
+fn fill(kind: &str) -> SExpr {
+    SExpr::list("fill").sym_field("type", kind)
+}
+
+/// Escape a free-form string for a quoted field of a KiCad 5 library
+fn escape_string(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
 }
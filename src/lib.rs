@@ -1,17 +1,23 @@
 pub mod checkpoint;
 pub mod cli;
 pub mod converter;
+pub mod courtyard;
+pub mod dump;
 pub mod easyeda;
 pub mod error;
 pub mod footprint_converter;
 pub mod kicad;
 pub mod library;
 pub mod model_converter;
+pub mod passives;
+pub mod property_map;
 pub mod symbol_converter;
 
 pub use cli::{Cli, KicadVersion};
 pub use converter::Converter;
-pub use easyeda::{EasyedaApi, FootprintImporter, SymbolImporter};
+pub use easyeda::{
+    ChainedSource, ComponentSource, EasyedaApi, FootprintImporter, LocalSource, SymbolImporter,
+};
 pub use error::{AppError, Result};
 pub use kicad::{FootprintExporter, ModelExporter, SymbolExporter};
 pub use library::LibraryManager;
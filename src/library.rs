@@ -1,4 +1,4 @@
-use crate::cli::Cli;
+use crate::cli::{Cli, KicadVersion};
 use crate::error::{AppError, KicadError, Result};
 use regex::Regex;
 use std::fs;
@@ -30,6 +30,7 @@ pub struct LibraryManager {
     model_lib_dir: PathBuf,
     model_lib_name: String,
     model_dir_name: String,
+    kicad_version: KicadVersion,
 }
 
 impl LibraryManager {
@@ -51,6 +52,7 @@ impl LibraryManager {
             model_lib_dir,
             model_lib_name: lib_name,
             model_dir_name,
+            kicad_version: KicadVersion::V6,
         }
     }
 
@@ -77,6 +79,7 @@ impl LibraryManager {
             model_lib_dir,
             model_lib_name,
             model_dir_name,
+            kicad_version: args.kicad_version(),
         })
     }
 
@@ -96,13 +99,52 @@ impl LibraryManager {
         &self.model_dir_name
     }
 
+    /// Opening lines of a new `.kicad_sym` file for the target KiCad version
+    fn symbol_lib_header(&self) -> String {
+        let mut header = format!(
+            "(kicad_symbol_lib\n  (version {})\n  (generator nlbn)",
+            self.kicad_version.symbol_format()
+        );
+        if let Some(generator_version) = self.kicad_version.generator_version() {
+            header.push_str(&format!(
+                "\n  (generator_version \"{}\")",
+                generator_version
+            ));
+        }
+        header
+    }
+
+    /// Replace the header of an existing `.kicad_sym` file written in an older
+    /// format, so the file does not claim a format its new symbols do not use.
+    /// Symbols already in the file stay as they are; newer KiCad reads them.
+    fn upgrade_symbol_lib_header(&self, content: &str) -> String {
+        let header_end = content.find("(symbol ").unwrap_or(content.len());
+        let header = &content[..header_end];
+        let version = Regex::new(r"\(version\s+(\d+)\)")
+            .ok()
+            .and_then(|re| re.captures(header))
+            .and_then(|caps| caps[1].parse::<u32>().ok());
+
+        match version {
+            Some(version) if version < self.kicad_version.symbol_format() => {
+                log::info!(
+                    "Upgrading symbol library from format {} to {}",
+                    version,
+                    self.kicad_version.symbol_format()
+                );
+                format!("{}\n  {}", self.symbol_lib_header(), &content[header_end..])
+            }
+            _ => content.to_string(),
+        }
+    }
+
     /// Create necessary output directories
     pub fn create_directories(&self) -> Result<()> {
         if let Some(symbol_lib_path) = &self.symbol_lib_override {
-            if let Some(parent) = symbol_lib_path.parent() {
-                if !parent.as_os_str().is_empty() {
-                    fs::create_dir_all(parent).map_err(KicadError::Io)?;
-                }
+            if let Some(parent) = symbol_lib_path.parent()
+                && !parent.as_os_str().is_empty()
+            {
+                fs::create_dir_all(parent).map_err(KicadError::Io)?;
             }
         } else {
             fs::create_dir_all(&self.output_path).map_err(KicadError::Io)?;
@@ -126,18 +168,18 @@ impl LibraryManager {
 
         // Check for v6 format
         let v6_pattern = format!(r#"\(symbol\s+"{}""#, regex::escape(component_name));
-        if let Ok(re) = Regex::new(&v6_pattern) {
-            if re.is_match(&content) {
-                return Ok(true);
-            }
+        if let Ok(re) = Regex::new(&v6_pattern)
+            && re.is_match(&content)
+        {
+            return Ok(true);
         }
 
         // Check for v5 format
         let v5_pattern = format!(r"DEF\s+{}\s+", regex::escape(component_name));
-        if let Ok(re) = Regex::new(&v5_pattern) {
-            if re.is_match(&content) {
-                return Ok(true);
-            }
+        if let Ok(re) = Regex::new(&v5_pattern)
+            && re.is_match(&content)
+        {
+            return Ok(true);
         }
 
         Ok(false)
@@ -155,18 +197,7 @@ impl LibraryManager {
         let _lock = SYMBOL_WRITE_LOCK.lock().unwrap();
 
         // Check if component exists (within lock to prevent TOCTOU)
-        let exists = if lib_path.exists() {
-            let content = fs::read_to_string(lib_path).map_err(KicadError::Io)?;
-
-            let v6_pattern = format!(r#"\(symbol\s+"{}""#, regex::escape(component_name));
-            if let Ok(re) = Regex::new(&v6_pattern) {
-                re.is_match(&content)
-            } else {
-                false
-            }
-        } else {
-            false
-        };
+        let exists = self.component_exists(lib_path, component_name)?;
 
         if exists && overwrite {
             // Update existing component
@@ -184,23 +215,36 @@ impl LibraryManager {
 
     /// Internal add component (assumes lock is held)
     fn add_component_internal(&self, lib_path: &Path, component_data: &str) -> Result<()> {
+        let is_v6 = component_data.contains("(symbol");
+
         let mut content = if lib_path.exists() {
             let existing = fs::read_to_string(lib_path).map_err(KicadError::Io)?;
-            existing.trim_end().trim_end_matches(')').to_string()
-        } else {
-            if component_data.contains("(symbol") {
-                String::from("(kicad_symbol_lib\n  (version 20211014)\n  (generator nlbn)")
+            if is_v6 {
+                let existing = self.upgrade_symbol_lib_header(&existing);
+                existing.trim_end().trim_end_matches(')').to_string()
             } else {
-                String::from("EESchema-LIBRARY Version 2.4\n#encoding utf-8")
+                let existing = existing.trim_end();
+                existing
+                    .strip_suffix("#End Library")
+                    .unwrap_or(existing)
+                    .trim_end()
+                    .to_string()
             }
+        } else if is_v6 {
+            self.symbol_lib_header()
+        } else {
+            String::from("EESchema-LIBRARY Version 2.4\n#encoding utf-8")
         };
 
         content.push('\n');
-        content.push_str(component_data);
-
-        if component_data.contains("(symbol") {
+        if is_v6 {
+            content.push_str(component_data);
             content.push('\n');
             content.push(')');
+        } else {
+            content.push_str("#\n");
+            content.push_str(component_data.trim_end());
+            content.push_str("\n#\n#End Library");
         }
         content.push('\n');
 
@@ -220,7 +264,9 @@ impl LibraryManager {
 
         // Try v6 format: find symbol block by matching parentheses
         let search = format!(r#"(symbol "{}""#, component_name);
-        if let Some(start) = content.find(&search) {
+        if content.contains(&search) {
+            let content = self.upgrade_symbol_lib_header(&content);
+            let start = content.find(&search).unwrap_or_default();
             // Walk back to consume leading whitespace/newline before (symbol
             let mut block_start = start;
             while block_start > 0 && content.as_bytes()[block_start - 1] == b' ' {
@@ -230,23 +276,7 @@ impl LibraryManager {
                 block_start -= 1;
             }
 
-            // Count parentheses from start to find the matching close
-            let mut depth = 0;
-            let mut block_end = start;
-            for (i, ch) in content[start..].char_indices() {
-                match ch {
-                    '(' => depth += 1,
-                    ')' => {
-                        depth -= 1;
-                        if depth == 0 {
-                            block_end = start + i + 1;
-                            break;
-                        }
-                    }
-                    _ => {}
-                }
-            }
-            if block_end > start {
+            if let Some(block_end) = sexpr_block_end(&content, start) {
                 let mut new_content = String::with_capacity(content.len());
                 new_content.push_str(&content[..block_start]);
                 new_content.push('\n');
@@ -259,22 +289,22 @@ impl LibraryManager {
 
         // Try v5 format
         let v5_start = format!("DEF {} ", component_name);
-        if let Some(start) = content.find(&v5_start) {
-            if let Some(end_offset) = content[start..].find("ENDDEF") {
-                let block_end = start + end_offset + "ENDDEF".len();
-                // Skip trailing newline
-                let block_end = if content[block_end..].starts_with('\n') {
-                    block_end + 1
-                } else {
-                    block_end
-                };
-                let mut new_content = String::with_capacity(content.len());
-                new_content.push_str(&content[..start]);
-                new_content.push_str(new_data);
-                new_content.push_str(&content[block_end..]);
-                fs::write(lib_path, &new_content).map_err(KicadError::Io)?;
-                return Ok(());
-            }
+        if let Some(start) = content.find(&v5_start)
+            && let Some(end_offset) = content[start..].find("ENDDEF")
+        {
+            let block_end = start + end_offset + "ENDDEF".len();
+            // Skip trailing newline
+            let block_end = if content[block_end..].starts_with('\n') {
+                block_end + 1
+            } else {
+                block_end
+            };
+            let mut new_content = String::with_capacity(content.len());
+            new_content.push_str(&content[..start]);
+            new_content.push_str(new_data);
+            new_content.push_str(&content[block_end..]);
+            fs::write(lib_path, &new_content).map_err(KicadError::Io)?;
+            return Ok(());
         }
 
         Err(
@@ -287,36 +317,57 @@ impl LibraryManager {
     pub fn add_component(&self, lib_path: &Path, component_data: &str) -> Result<()> {
         // Lock to prevent concurrent writes to the same symbol library file
         let _lock = SYMBOL_WRITE_LOCK.lock().unwrap();
+        self.add_component_internal(lib_path, component_data)
+    }
 
-        let mut content = if lib_path.exists() {
-            // Read existing file and remove the closing parenthesis
-            let existing = fs::read_to_string(lib_path).map_err(KicadError::Io)?;
-            // Remove trailing ')' and whitespace
-            existing.trim_end().trim_end_matches(')').to_string()
+    /// Add or replace the entry of a KiCad 5 symbol in the `.dcm` file next to its library
+    pub fn add_or_update_doc(
+        &self,
+        lib_path: &Path,
+        component_name: &str,
+        entry: &str,
+    ) -> Result<()> {
+        let _lock = SYMBOL_WRITE_LOCK.lock().unwrap();
+
+        let doc_path = lib_path.with_extension("dcm");
+        let existing = if doc_path.exists() {
+            fs::read_to_string(&doc_path).map_err(KicadError::Io)?
         } else {
-            // Create new library file with header (v6 format with proper formatting)
-            if component_data.contains("(symbol") {
-                // v6 format - match Python's formatting exactly
-                String::from("(kicad_symbol_lib\n  (version 20211014)\n  (generator nlbn)")
-            } else {
-                // v5 format
-                String::from("EESchema-LIBRARY Version 2.4\n#encoding utf-8")
-            }
+            String::new()
         };
 
-        // Append component
-        content.push('\n');
-        content.push_str(component_data);
+        // Entries are "$CMP name" ... "$ENDCMP" blocks
+        let mut entries: Vec<String> = Vec::new();
+        let mut current: Option<String> = None;
+        for line in existing.lines() {
+            if line.starts_with("$CMP ") {
+                current = Some(String::new());
+            }
+            if let Some(block) = current.as_mut() {
+                block.push_str(line);
+                block.push('\n');
+            }
+            if line.starts_with("$ENDCMP")
+                && let Some(block) = current.take()
+            {
+                entries.push(block);
+            }
+        }
 
-        // Add closing parenthesis for v6 format
-        if component_data.contains("(symbol") {
-            content.push('\n');
-            content.push(')');
+        let start = format!("$CMP {}\n", component_name);
+        match entries.iter_mut().find(|block| block.starts_with(&start)) {
+            Some(block) => *block = entry.to_string(),
+            None => entries.push(entry.to_string()),
         }
-        content.push('\n');
 
-        fs::write(lib_path, content).map_err(KicadError::Io)?;
+        let mut content = String::from("EESchema-DOCLIB  Version 2.0\n");
+        for block in &entries {
+            content.push_str("#\n");
+            content.push_str(block);
+        }
+        content.push_str("#\n#End Doc Library\n");
 
+        fs::write(&doc_path, content).map_err(KicadError::Io)?;
         Ok(())
     }
 
@@ -329,61 +380,7 @@ impl LibraryManager {
     ) -> Result<()> {
         // Lock to prevent concurrent writes to the same symbol library file
         let _lock = SYMBOL_WRITE_LOCK.lock().unwrap();
-
-        let content = fs::read_to_string(lib_path).map_err(KicadError::Io)?;
-
-        // Try v6 format: find symbol block by matching parentheses
-        let search = format!(r#"(symbol "{}""#, component_name);
-        if let Some(start) = content.find(&search) {
-            let block_start = content[..start].rfind('(').unwrap_or(start);
-            let mut depth = 0;
-            let mut block_end = block_start;
-            for (i, ch) in content[block_start..].char_indices() {
-                match ch {
-                    '(' => depth += 1,
-                    ')' => {
-                        depth -= 1;
-                        if depth == 0 {
-                            block_end = block_start + i + 1;
-                            break;
-                        }
-                    }
-                    _ => {}
-                }
-            }
-            if block_end > block_start {
-                let mut new_content = String::with_capacity(content.len());
-                new_content.push_str(&content[..block_start]);
-                new_content.push_str(new_data);
-                new_content.push_str(&content[block_end..]);
-                fs::write(lib_path, &new_content).map_err(KicadError::Io)?;
-                return Ok(());
-            }
-        }
-
-        // Try v5 format
-        let v5_start = format!("DEF {} ", component_name);
-        if let Some(start) = content.find(&v5_start) {
-            if let Some(end_offset) = content[start..].find("ENDDEF") {
-                let block_end = start + end_offset + "ENDDEF".len();
-                let block_end = if content[block_end..].starts_with('\n') {
-                    block_end + 1
-                } else {
-                    block_end
-                };
-                let mut new_content = String::with_capacity(content.len());
-                new_content.push_str(&content[..start]);
-                new_content.push_str(new_data);
-                new_content.push_str(&content[block_end..]);
-                fs::write(lib_path, &new_content).map_err(KicadError::Io)?;
-                return Ok(());
-            }
-        }
-
-        Err(
-            KicadError::SymbolExport(format!("Component {} not found in library", component_name))
-                .into(),
-        )
+        self.update_component_internal(lib_path, component_name, new_data)
     }
 
     /// Atomic write: write to temp file with buffered I/O, then rename
@@ -499,8 +496,13 @@ impl LibraryManager {
         if let Some(path) = &self.symbol_lib_override {
             path.clone()
         } else {
+            let extension = if self.kicad_version == KicadVersion::V5 {
+                "lib"
+            } else {
+                "kicad_sym"
+            };
             self.output_path
-                .join(format!("{}.kicad_sym", self.lib_name))
+                .join(format!("{}.{}", self.lib_name, extension))
         }
     }
 
@@ -523,6 +525,37 @@ impl LibraryManager {
     }
 }
 
+/// End (exclusive) of the list opening at `start`; parentheses inside quoted
+/// strings are skipped
+fn sexpr_block_end(content: &str, start: usize) -> Option<usize> {
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, ch) in content[start..].char_indices() {
+        if in_string {
+            match ch {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match ch {
+            '"' => in_string = true,
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(start + i + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -646,6 +679,147 @@ mod tests {
         let _ = fs::remove_dir_all(root);
     }
 
+    #[test]
+    fn new_symbol_library_uses_target_format() {
+        let root = temp_dir("kicad8-lib");
+        let cli = Cli::try_parse_from([
+            "nlbn",
+            "--lcsc-id",
+            "C2040",
+            "--symbol",
+            "--kicad-version",
+            "8",
+            "-o",
+            root.to_str().unwrap(),
+        ])
+        .unwrap();
+
+        let manager = LibraryManager::from_cli(&cli).unwrap();
+        manager.create_directories().unwrap();
+        manager
+            .add_or_update_component(
+                &manager.get_symbol_lib_path(),
+                "Part_C1",
+                r#"  (symbol "Part_C1")"#,
+                false,
+            )
+            .unwrap();
+
+        let content = fs::read_to_string(manager.get_symbol_lib_path()).unwrap();
+        assert!(content.starts_with(
+            "(kicad_symbol_lib\n  (version 20231120)\n  (generator nlbn)\n  (generator_version \"8.0\")"
+        ));
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn newer_symbols_upgrade_an_older_library_header() {
+        let root = temp_dir("kicad6-to-8-lib");
+        let cli = Cli::try_parse_from([
+            "nlbn",
+            "--lcsc-id",
+            "C2040",
+            "--symbol",
+            "--kicad-version",
+            "8",
+            "-o",
+            root.to_str().unwrap(),
+        ])
+        .unwrap();
+
+        let manager = LibraryManager::from_cli(&cli).unwrap();
+        manager.create_directories().unwrap();
+        let lib_path = manager.get_symbol_lib_path();
+        fs::write(
+            &lib_path,
+            "(kicad_symbol_lib (version 20211014) (generator kicad_symbol_editor)\n  (symbol \"Old_C1\")\n)\n",
+        )
+        .unwrap();
+
+        manager
+            .add_or_update_component(&lib_path, "Part_C2", r#"  (symbol "Part_C2")"#, false)
+            .unwrap();
+        let content = fs::read_to_string(&lib_path).unwrap();
+        assert!(content.starts_with(
+            "(kicad_symbol_lib\n  (version 20231120)\n  (generator nlbn)\n  (generator_version \"8.0\")\n  (symbol \"Old_C1\")\n"
+        ));
+        assert!(content.ends_with("  (symbol \"Part_C2\")\n)\n"));
+
+        // Updating a symbol upgrades the header as well
+        fs::write(
+            &lib_path,
+            "(kicad_symbol_lib (version 20211014) (generator nlbn)\n  (symbol \"Old_C1\")\n)\n",
+        )
+        .unwrap();
+        manager
+            .add_or_update_component(
+                &lib_path,
+                "Old_C1",
+                r#"  (symbol "Old_C1" (in_bom yes))"#,
+                true,
+            )
+            .unwrap();
+        let content = fs::read_to_string(&lib_path).unwrap();
+        assert!(content.starts_with("(kicad_symbol_lib\n  (version 20231120)\n"));
+        assert!(content.ends_with("  (symbol \"Old_C1\" (in_bom yes))\n)\n"));
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn writes_kicad5_library_and_doc_file() {
+        let root = temp_dir("kicad5-lib");
+        let cli = Cli::try_parse_from([
+            "nlbn",
+            "--lcsc-id",
+            "C2040",
+            "--symbol",
+            "--kicad-version",
+            "5",
+            "-o",
+            root.to_str().unwrap(),
+        ])
+        .unwrap();
+        cli.validate().unwrap();
+
+        let manager = LibraryManager::from_cli(&cli).unwrap();
+        manager.create_directories().unwrap();
+        let lib_path = manager.get_symbol_lib_path();
+        assert_eq!(lib_path.extension().unwrap(), "lib");
+
+        for name in ["Part_C1", "Part_C2"] {
+            let def = format!("DEF {} U 0 40 Y Y 1 F N\nDRAW\nENDDRAW\nENDDEF\n", name);
+            let status = manager
+                .add_or_update_component(&lib_path, name, &def, false)
+                .unwrap();
+            assert_eq!(status, SymbolWriteStatus::Added);
+            manager
+                .add_or_update_doc(&lib_path, name, &format!("$CMP {}\nD Old\n$ENDCMP\n", name))
+                .unwrap();
+        }
+        let status = manager
+            .add_or_update_component(&lib_path, "Part_C1", "DEF Part_C1 U\nENDDEF\n", false)
+            .unwrap();
+        assert_eq!(status, SymbolWriteStatus::Skipped);
+        manager
+            .add_or_update_doc(&lib_path, "Part_C1", "$CMP Part_C1\nD New\n$ENDCMP\n")
+            .unwrap();
+
+        let lib = fs::read_to_string(&lib_path).unwrap();
+        assert!(lib.starts_with("EESchema-LIBRARY Version 2.4\n#encoding utf-8\n#\nDEF Part_C1 "));
+        assert!(lib.ends_with("ENDDEF\n#\n#End Library\n"));
+        assert_eq!(lib.matches("#End Library").count(), 1);
+
+        let doc = fs::read_to_string(lib_path.with_extension("dcm")).unwrap();
+        assert_eq!(
+            doc,
+            "EESchema-DOCLIB  Version 2.0\n#\n$CMP Part_C1\nD New\n$ENDCMP\n#\n$CMP Part_C2\nD Old\n$ENDCMP\n#\n#End Doc Library\n"
+        );
+
+        let _ = fs::remove_dir_all(root);
+    }
+
     #[test]
     fn skips_existing_symbol_without_overwrite() {
         let root = temp_dir("symbol-skip");
@@ -666,6 +840,33 @@ mod tests {
         let _ = fs::remove_dir_all(root);
     }
 
+    #[test]
+    fn replaces_symbol_with_parentheses_in_strings() {
+        let root = temp_dir("symbol-update");
+        let manager = LibraryManager::new(&root);
+        manager.create_directories().unwrap();
+        let lib_path = manager.get_symbol_lib_path();
+
+        let old = r#"  (symbol "Part_C1" (property "Description" "1) \"smiley :)\""))"#;
+        let new = r#"  (symbol "Part_C1" (property "Description" "new"))"#;
+        manager
+            .add_or_update_component(&lib_path, "Part_C1", old, false)
+            .unwrap();
+        manager
+            .add_or_update_component(&lib_path, "Part_C2", r#"  (symbol "Part_C2")"#, false)
+            .unwrap();
+        let status = manager
+            .add_or_update_component(&lib_path, "Part_C1", new, true)
+            .unwrap();
+
+        let content = fs::read_to_string(&lib_path).unwrap();
+        assert_eq!(status, SymbolWriteStatus::Updated);
+        assert!(content.contains(new) && !content.contains("smiley"));
+        assert!(content.contains("\n  (symbol \"Part_C2\")\n)\n"));
+
+        let _ = fs::remove_dir_all(root);
+    }
+
     #[test]
     fn skips_existing_footprint_without_overwrite() {
         let root = temp_dir("footprint-skip");
@@ -1,6 +1,8 @@
 use clap::{CommandFactory, Parser};
+use futures_util::StreamExt;
 use indicatif::{ProgressBar, ProgressStyle};
 use nlbn::checkpoint::{append_checkpoint, load_checkpoint};
+use nlbn::cli::{Command, SearchArgs};
 use nlbn::*;
 use std::process;
 use std::sync::Arc;
@@ -31,7 +33,7 @@ async fn main() {
     }
 
     // Parse CLI arguments
-    let args = Cli::parse();
+    let mut args = Cli::parse();
 
     if args.prompt {
         println!("{}", Cli::prompt_examples());
@@ -43,8 +45,12 @@ async fn main() {
         log::set_max_level(log::LevelFilter::Debug);
     }
 
-    // Run the conversion
-    if let Err(e) = run(args).await {
+    // Run the search or the conversion
+    let result = match args.command.take() {
+        Some(Command::Search(search)) => run_search(args, search).await,
+        None => run(args).await,
+    };
+    if let Err(e) = result {
         eprintln!("Error: {}", e);
         process::exit(1);
     }
@@ -59,10 +65,96 @@ fn print_version_and_help() {
     println!();
 }
 
+async fn run_search(mut args: Cli, search: SearchArgs) -> error::Result<()> {
+    if args.offline {
+        return Err(AppError::Other(
+            "search needs network access and cannot be used with --offline".to_string(),
+        ));
+    }
+
+    // One client for the search and the lookups, so they share its rate limiter
+    let api = Arc::new(EasyedaApi::with_config(args.api_config()?)?);
+    let mut results = api.search(&search.query, search.limit).await?;
+
+    if results.is_empty() {
+        println!("No parts found for \"{}\"", search.query);
+        return Ok(());
+    }
+
+    // The search response does not say whether a 3D model exists; the component data does
+    let source = easyeda::source_with_upstream(&args, Some(Box::new(api)))?;
+    let lookups: Vec<_> = futures_util::stream::iter(&results)
+        .map(|r| source.get_component_data(&r.lcsc_id))
+        .buffered(args.parallel.max(1))
+        .collect()
+        .await;
+    for (result, lookup) in results.iter_mut().zip(lookups) {
+        match lookup {
+            Ok(data) => {
+                result.has_3d_model = Some(data.model_3d.is_some());
+                if result.manufacturer.is_empty() {
+                    result.manufacturer = data.manufacturer;
+                }
+            }
+            Err(e) => log::debug!("Failed to look up {}: {}", result.lcsc_id, e),
+        }
+    }
+
+    print_search_results(&results);
+
+    if search.convert_first {
+        let top = &results[0];
+        println!("\nConverting top hit {} ({})", top.lcsc_id, top.mpn);
+        args.lcsc_id = Some(top.lcsc_id.clone());
+        args.batch = None;
+        if !args.symbol && !args.footprint && !args.model_3d {
+            args.full = true;
+        }
+        return run(args).await;
+    }
+
+    Ok(())
+}
+
+fn print_search_results(results: &[easyeda::SearchResult]) {
+    let width = |header: &str, column: &dyn Fn(&easyeda::SearchResult) -> usize| {
+        results
+            .iter()
+            .map(column)
+            .max()
+            .unwrap_or(0)
+            .max(header.len())
+    };
+    let id_w = width("LCSC", &|r| r.lcsc_id.len());
+    let mpn_w = width("MPN", &|r| r.mpn.chars().count());
+    let mfr_w = width("Manufacturer", &|r| r.manufacturer.chars().count());
+    let pkg_w = width("Package", &|r| r.package.chars().count());
+
+    println!(
+        "{:id_w$}  {:mpn_w$}  {:mfr_w$}  {:pkg_w$}  3D",
+        "LCSC", "MPN", "Manufacturer", "Package"
+    );
+    for r in results {
+        let model = match r.has_3d_model {
+            Some(true) => "yes",
+            Some(false) => "no",
+            None => "?",
+        };
+        println!(
+            "{:id_w$}  {:mpn_w$}  {:mfr_w$}  {:pkg_w$}  {}",
+            r.lcsc_id, r.mpn, r.manufacturer, r.package, model
+        );
+    }
+}
+
 async fn run(args: Cli) -> error::Result<()> {
     // Validate arguments
     args.validate()?;
 
+    if let Some(path) = &args.from_file {
+        return run_from_file(&args, path).await;
+    }
+
     // Get list of LCSC IDs to process
     let lcsc_ids = args.get_lcsc_ids()?;
     let is_batch = lcsc_ids.len() > 1;
@@ -104,8 +196,8 @@ async fn run(args: Cli) -> error::Result<()> {
         }
     }
 
-    // Initialize API
-    let api = Arc::new(EasyedaApi::new());
+    // Initialize component source
+    let api: Arc<dyn ComponentSource> = Arc::from(easyeda::source_from_cli(&args)?);
 
     // Track statistics
     let success_count = Arc::new(AtomicUsize::new(0));
@@ -132,7 +224,7 @@ async fn run(args: Cli) -> error::Result<()> {
         let mut join_set = JoinSet::new();
         let pb = Arc::new(pb);
 
-        for (_index, lcsc_id) in lcsc_ids.into_iter().enumerate() {
+        for lcsc_id in lcsc_ids.into_iter() {
             let sem = semaphore.clone();
             let api = api.clone();
             let lib_manager = lib_manager.clone();
@@ -146,9 +238,9 @@ async fn run(args: Cli) -> error::Result<()> {
             join_set.spawn(async move {
                 let _permit = sem.acquire().await.expect("semaphore closed");
 
-                pb.set_message(format!("{}", lcsc_id));
+                pb.set_message(lcsc_id.to_string());
 
-                match process_component(&args, &api, &lib_manager, &lcsc_id).await {
+                match process_component(&args, api.as_ref(), &lib_manager, &lcsc_id).await {
                     Ok(_) => {
                         success_count.fetch_add(1, Ordering::Relaxed);
                         append_checkpoint(&checkpoint_path, &lcsc_id);
@@ -173,14 +265,14 @@ async fn run(args: Cli) -> error::Result<()> {
         pb.finish_and_clear();
     } else {
         // Sequential processing mode
-        for (_index, lcsc_id) in lcsc_ids.iter().enumerate() {
+        for lcsc_id in lcsc_ids.iter() {
             if is_batch {
-                pb.set_message(format!("{}", lcsc_id));
+                pb.set_message(lcsc_id.to_string());
             } else {
                 log::info!("Starting conversion for LCSC ID: {}", lcsc_id);
             }
 
-            match process_component(&args, &api, &lib_manager, lcsc_id).await {
+            match process_component(&args, api.as_ref(), &lib_manager, lcsc_id).await {
                 Ok(_) => {
                     success_count.fetch_add(1, Ordering::Relaxed);
                     append_checkpoint(&checkpoint_path, lcsc_id);
@@ -241,32 +333,103 @@ async fn run(args: Cli) -> error::Result<()> {
     Ok(())
 }
 
+async fn run_from_file(args: &Cli, path: &std::path::Path) -> error::Result<()> {
+    let component_data = easyeda::load_exported_component(path)?;
+    let part_id = easyeda::exported_part_id(path, &component_data);
+
+    let lib_manager = LibraryManager::from_cli(args)?;
+    lib_manager.create_directories()?;
+
+    // Only the 3D model still comes from EasyEDA
+    let api = easyeda::source_from_cli(args)?;
+    convert_component(args, api.as_ref(), &lib_manager, &component_data, &part_id).await?;
+
+    println!("\n✓ Conversion complete!");
+    println!("Output directory: {}", args.output.display());
+    Ok(())
+}
+
 async fn process_component(
     args: &Cli,
-    api: &EasyedaApi,
+    api: &dyn ComponentSource,
     lib_manager: &LibraryManager,
     lcsc_id: &str,
 ) -> error::Result<()> {
     // Fetch component data from EasyEDA API
-    let component_data = api.get_component_data(lcsc_id).await?;
+    let component_data = match &args.dump_raw {
+        Some(dump_dir) => {
+            let api_response = api.fetch_api_response(lcsc_id).await?;
+            let component_data = EasyedaApi::parse_component_data(lcsc_id, api_response.clone());
+            dump::dump_component(
+                dump_dir,
+                lcsc_id,
+                &api_response,
+                component_data.as_ref().ok(),
+            )?;
+            component_data?
+        }
+        None => api.get_component_data(lcsc_id).await?,
+    };
 
     log::info!("Fetched component: {}", component_data.title);
 
+    convert_component(args, api, lib_manager, &component_data, lcsc_id).await
+}
+
+async fn convert_component(
+    args: &Cli,
+    api: &dyn ComponentSource,
+    lib_manager: &LibraryManager,
+    component_data: &easyeda::ComponentData,
+    lcsc_id: &str,
+) -> error::Result<()> {
+    // Generic chip passives use KiCad's own symbol and footprint libraries
+    if args.stock_passives
+        && let Some(passive) = passives::classify(component_data)
+    {
+        if args.symbol || args.full {
+            log::info!("Converting symbol as stock {}...", passive.symbol_name());
+            symbol_converter::convert_stock_passive(
+                args,
+                component_data,
+                lib_manager,
+                lcsc_id,
+                &passive,
+            )?;
+        }
+        if args.footprint || args.full || args.model_3d {
+            println!(
+                "Using stock footprint {} for {}, skipping footprint and 3D model",
+                passive.footprint(),
+                lcsc_id
+            );
+        }
+        return Ok(());
+    }
+
     // Process symbol (if requested)
     if args.symbol || args.full {
-        log::info!("Converting symbol...");
-        symbol_converter::convert_symbol(args, &component_data, lib_manager, lcsc_id)?;
+        if component_data.data_str.is_empty() && args.from_file.is_some() {
+            println!("No symbol in {}, skipping", lcsc_id);
+        } else {
+            log::info!("Converting symbol...");
+            symbol_converter::convert_symbol(args, component_data, lib_manager, lcsc_id)?;
+        }
     }
 
     // Process footprint (if requested)
     if args.footprint || args.full {
-        log::info!("Converting footprint...");
-        footprint_converter::convert_footprint(args, &component_data, lib_manager, lcsc_id)?;
+        if component_data.package_detail.is_empty() && args.from_file.is_some() {
+            println!("No footprint in {}, skipping", lcsc_id);
+        } else {
+            log::info!("Converting footprint...");
+            footprint_converter::convert_footprint(args, component_data, lib_manager, lcsc_id)?;
+        }
     }
 
     // Process 3D model (if requested)
     if args.model_3d || args.full {
-        model_converter::convert_3d_model(args, api, &component_data, lib_manager, lcsc_id).await?;
+        model_converter::convert_3d_model(args, api, component_data, lib_manager, lcsc_id).await?;
     }
 
     Ok(())
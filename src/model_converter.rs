@@ -1,13 +1,13 @@
 use crate::cli::Cli;
 use crate::converter::sanitize_name;
-use crate::easyeda::{ComponentData, EasyedaApi};
+use crate::easyeda::{ComponentData, ComponentSource};
 use crate::error::Result;
 use crate::kicad::ModelExporter;
 use crate::library::{FileWriteStatus, LibraryManager};
 
 pub async fn convert_3d_model(
     args: &Cli,
-    api: &EasyedaApi,
+    api: &dyn ComponentSource,
     component_data: &ComponentData,
     lib_manager: &LibraryManager,
     lcsc_id: &str,
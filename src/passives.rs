@@ -0,0 +1,250 @@
+use crate::easyeda::ComponentData;
+use crate::kicad;
+use crate::symbol_converter::designator_prefix;
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Imperial chip size and its metric name, as used by KiCad's stock footprints
+const CHIP_SIZES: &[(&str, &str)] = &[
+    ("0201", "0603"),
+    ("0402", "1005"),
+    ("0603", "1608"),
+    ("0805", "2012"),
+    ("1206", "3216"),
+    ("1210", "3225"),
+    ("2010", "5025"),
+    ("2512", "6332"),
+];
+
+/// Chip package names such as `R0603`, `C0402`, `0805` or `RES_1206`
+static CHIP_PACKAGE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^(?:R|C|RES|CAP)?[-_ ]?(\d{4})(?:[-_ ]|$)").unwrap());
+
+/// Category words that rule out a plain two-terminal chip part
+const EXCLUDED_WORDS: &[&str] = &[
+    "array",
+    "network",
+    "tantalum",
+    "electrolytic",
+    "polarized",
+    "polymer",
+    "trimmer",
+    "potentiometer",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PassiveKind {
+    Resistor,
+    Capacitor,
+}
+
+/// A generic chip resistor or capacitor that maps onto KiCad's stock libraries
+#[derive(Debug, Clone, PartialEq)]
+pub struct StockPassive {
+    pub kind: PassiveKind,
+    /// Imperial size, e.g. `0603`
+    pub size: &'static str,
+    /// Metric size, e.g. `1608`
+    pub metric: &'static str,
+    /// Resistance or capacitance from `c_para`, if given
+    pub value: Option<String>,
+}
+
+impl StockPassive {
+    /// Designator prefix and name of the base symbol, as in the `Device` library
+    pub fn symbol_name(&self) -> &'static str {
+        match self.kind {
+            PassiveKind::Resistor => "R",
+            PassiveKind::Capacitor => "C",
+        }
+    }
+
+    /// Stock footprint, e.g. `Resistor_SMD:R_0603_1608Metric`
+    pub fn footprint(&self) -> String {
+        let (lib, prefix) = match self.kind {
+            PassiveKind::Resistor => ("Resistor_SMD", "R"),
+            PassiveKind::Capacitor => ("Capacitor_SMD", "C"),
+        };
+        format!("{}:{}_{}_{}Metric", lib, prefix, self.size, self.metric)
+    }
+
+    /// Copy of `Device:R`/`Device:C` for derived symbols to extend.
+    ///
+    /// KiCad only resolves `extends` within one library file, so the base
+    /// symbol is written next to the parts that use it.
+    pub fn base_symbol(&self) -> kicad::KiSymbol {
+        let name = self.symbol_name();
+        // Pins 3.81 mm above and below the origin, pointing at the body
+        let pin = |number: &str, pos_y: f64, rotation: i32, length: f64| kicad::KiPin {
+            number: number.to_string(),
+            name: "~".to_string(),
+            pin_type: kicad::PinType::Passive,
+            style: kicad::PinStyle::Line,
+            pos_x: 0.0,
+            pos_y,
+            rotation,
+            length,
+            name_visible: false,
+            number_visible: false,
+        };
+
+        let mut symbol = kicad::KiSymbol {
+            name: name.to_string(),
+            reference: name.to_string(),
+            value: name.to_string(),
+            description: match self.kind {
+                PassiveKind::Resistor => "Resistor".to_string(),
+                PassiveKind::Capacitor => "Unpolarized capacitor".to_string(),
+            },
+            keywords: match self.kind {
+                PassiveKind::Resistor => "R res resistor".to_string(),
+                PassiveKind::Capacitor => "cap capacitor".to_string(),
+            },
+            footprint: String::new(),
+            datasheet: "~".to_string(),
+            manufacturer: String::new(),
+            lcsc_id: String::new(),
+            jlc_id: String::new(),
+            properties: Vec::new(),
+            extends: None,
+            pins: Vec::new(),
+            rectangles: Vec::new(),
+            circles: Vec::new(),
+            arcs: Vec::new(),
+            polylines: Vec::new(),
+            beziers: Vec::new(),
+            texts: Vec::new(),
+            units: Vec::new(),
+        };
+
+        // Symbol coordinates are in px (0.254 mm)
+        match self.kind {
+            PassiveKind::Resistor => {
+                symbol.rectangles.push(kicad::KiRectangle {
+                    x1: -4.0,
+                    y1: -10.0,
+                    x2: 4.0,
+                    y2: 10.0,
+                    stroke_width: 1.0,
+                    fill: false,
+                });
+                symbol.pins.push(pin("1", 15.0, 90, 5.0));
+                symbol.pins.push(pin("2", -15.0, 270, 5.0));
+            }
+            PassiveKind::Capacitor => {
+                for y in [-3.0, 3.0] {
+                    symbol.polylines.push(kicad::KiPolyline {
+                        points: vec![(-8.0, y), (8.0, y)],
+                        stroke_width: 2.0,
+                        fill: false,
+                    });
+                }
+                symbol.pins.push(pin("1", 15.0, 90, 11.0));
+                symbol.pins.push(pin("2", -15.0, 270, 11.0));
+            }
+        }
+
+        symbol
+    }
+}
+
+/// Classify a part as a generic chip resistor or capacitor.
+///
+/// Needs an R or C designator (from `c_para.pre` or the category) and a
+/// chip package in `c_para.package`; arrays, polarized and adjustable parts
+/// are left to the normal conversion.
+pub fn classify(component_data: &ComponentData) -> Option<StockPassive> {
+    let kind = match designator_prefix(component_data, "").as_str() {
+        "R" => PassiveKind::Resistor,
+        "C" => PassiveKind::Capacitor,
+        _ => return None,
+    };
+
+    let context =
+        format!("{} {}", component_data.category, component_data.description).to_lowercase();
+    if EXCLUDED_WORDS.iter().any(|w| context.contains(w)) {
+        return None;
+    }
+
+    let package = component_data.c_para.get("package")?;
+    let size = CHIP_PACKAGE.captures(package.trim())?.get(1)?.as_str();
+    let (size, metric) = CHIP_SIZES.iter().find(|(imperial, _)| *imperial == size)?;
+
+    let value_key = match kind {
+        PassiveKind::Resistor => "Resistance",
+        PassiveKind::Capacitor => "Capacitance",
+    };
+    let value = component_data
+        .c_para
+        .get(value_key)
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty());
+
+    Some(StockPassive {
+        kind,
+        size,
+        metric,
+        value,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn component(c_para: &[(&str, &str)], description: &str) -> ComponentData {
+        let mut data: ComponentData = serde_json::from_value(serde_json::json!({
+            "lcsc_id": "C25804", "title": "0603WAF1002T5E", "description": description,
+            "data_str": [], "bbox_x": 0.0, "bbox_y": 0.0,
+            "package_detail": [], "package_bbox_x": 0.0, "package_bbox_y": 0.0,
+            "model_3d": null, "manufacturer": "", "datasheet": "", "jlc_id": ""
+        }))
+        .unwrap();
+        data.c_para = c_para
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        data
+    }
+
+    #[test]
+    fn classifies_chip_resistors_and_capacitors() {
+        let resistor = classify(&component(
+            &[("pre", "R?"), ("package", "R0603"), ("Resistance", "10kΩ")],
+            "",
+        ))
+        .unwrap();
+        assert_eq!(resistor.footprint(), "Resistor_SMD:R_0603_1608Metric");
+        assert_eq!(resistor.value.as_deref(), Some("10kΩ"));
+
+        let capacitor = classify(&component(
+            &[("package", "C0402")],
+            "100nF 50V X7R 0402 Multilayer Ceramic Capacitors MLCC - SMD/SMT",
+        ))
+        .unwrap();
+        assert_eq!(capacitor.footprint(), "Capacitor_SMD:C_0402_1005Metric");
+        assert_eq!(capacitor.symbol_name(), "C");
+    }
+
+    #[test]
+    fn leaves_other_parts_alone() {
+        // Resistor array in a chip-sized package
+        assert!(
+            classify(&component(
+                &[("pre", "RN?"), ("package", "RES-ARRAY-SMD_0603-8P")],
+                "Resistor Networks & Arrays",
+            ))
+            .is_none()
+        );
+        // Tantalum capacitor
+        assert!(
+            classify(&component(
+                &[("pre", "C?"), ("package", "1206")],
+                "Tantalum Capacitors",
+            ))
+            .is_none()
+        );
+        // MCU
+        assert!(classify(&component(&[("pre", "U?"), ("package", "LQFP-48")], "")).is_none());
+    }
+}
@@ -0,0 +1,139 @@
+use crate::error::{AppError, Result};
+use indexmap::IndexMap;
+use std::path::Path;
+
+/// `c_para` keys exported as hidden symbol properties unless overridden
+const DEFAULT_MAPPING: &[(&str, &str)] = &[
+    ("Value", "Value"),
+    ("Tolerance", "Tolerance"),
+    ("Voltage", "Voltage"),
+    ("Power", "Power"),
+    ("BOM_Manufacturer Part", "Manufacturer Part"),
+    ("Manufacturer Part", "Manufacturer Part"),
+    ("BOM_Supplier Part", "Supplier Part"),
+    ("Supplier Part", "Supplier Part"),
+];
+
+/// Maps EasyEDA `c_para` keys to KiCad symbol property names.
+///
+/// Loaded from a TOML table of `"c_para key" = "Property Name"`; an empty
+/// name drops a default entry:
+///
+/// ```toml
+/// "BOM_Manufacturer Part" = "MPN"
+/// Resistance = "Resistance"
+/// Power = ""
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PropertyMap {
+    entries: IndexMap<String, String>,
+}
+
+impl Default for PropertyMap {
+    fn default() -> Self {
+        Self {
+            entries: DEFAULT_MAPPING
+                .iter()
+                .map(|(key, name)| (key.to_string(), name.to_string()))
+                .collect(),
+        }
+    }
+}
+
+impl PropertyMap {
+    /// Defaults overlaid with the entries of a TOML file
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| AppError::Other(format!("Failed to read {}: {}", path.display(), e)))?;
+        let table: IndexMap<String, String> = toml::from_str(&content)
+            .map_err(|e| AppError::Other(format!("Failed to parse {}: {}", path.display(), e)))?;
+
+        let mut map = Self::default();
+        for (key, name) in table {
+            map.set(key, name);
+        }
+        Ok(map)
+    }
+
+    /// Map `key` to property `name`; an empty name removes the mapping
+    pub fn set(&mut self, key: impl Into<String>, name: impl Into<String>) {
+        let key = key.into();
+        let name = name.into();
+        if name.trim().is_empty() {
+            self.entries.shift_remove(&key);
+        } else {
+            self.entries.insert(key, name);
+        }
+    }
+
+    /// Parse a `KEY=NAME` command-line entry; a bare `KEY` keeps the key as the name
+    pub fn set_from_arg(&mut self, arg: &str) -> Result<()> {
+        let (key, name) = arg.split_once('=').unwrap_or((arg, arg));
+        if key.trim().is_empty() {
+            return Err(AppError::Other(format!(
+                "Invalid --property '{}', expected KEY=NAME",
+                arg
+            )));
+        }
+        self.set(key.trim(), name.trim());
+        Ok(())
+    }
+
+    /// Properties for the mapped keys present in `c_para`, in `c_para` order.
+    ///
+    /// Empty values are skipped and each property name is used once, so
+    /// `BOM_Manufacturer Part` and `Manufacturer Part` do not both appear.
+    pub fn apply(&self, c_para: &IndexMap<String, String>) -> Vec<(String, String)> {
+        let mut properties: Vec<(String, String)> = Vec::new();
+
+        for (key, value) in c_para {
+            let Some(name) = self.entries.get(key) else {
+                continue;
+            };
+            let value = value.trim();
+            if value.is_empty() || properties.iter().any(|(n, _)| n == name) {
+                continue;
+            }
+            properties.push((name.clone(), value.to_string()));
+        }
+
+        properties
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_c_para_in_source_order() {
+        let c_para: IndexMap<String, String> = [
+            ("pre", "R?"),
+            ("Tolerance", "±1%"),
+            ("BOM_Supplier Part", "C25804"),
+            ("Resistance", "10kΩ"),
+            ("BOM_Manufacturer Part", "0603WAF1002T5E"),
+            ("Manufacturer Part", "ignored duplicate"),
+            ("Power", ""),
+        ]
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+
+        let mut map = PropertyMap::default();
+        map.set_from_arg("Resistance").unwrap();
+        map.set_from_arg("BOM_Supplier Part=").unwrap();
+
+        assert_eq!(
+            map.apply(&c_para),
+            vec![
+                ("Tolerance".to_string(), "±1%".to_string()),
+                ("Resistance".to_string(), "10kΩ".to_string()),
+                (
+                    "Manufacturer Part".to_string(),
+                    "0603WAF1002T5E".to_string()
+                ),
+            ]
+        );
+    }
+}
@@ -1,9 +1,11 @@
-use crate::cli::Cli;
-use crate::converter::{Converter, sanitize_name};
-use crate::easyeda::{ComponentData, SymbolImporter};
+use crate::cli::{Cli, KicadVersion};
+use crate::converter::{Converter, CurveOptions, flatten_cubic, quad_to_cubic, sanitize_name};
+use crate::easyeda::svg_parser::{SvgCommand, parse_svg_path};
+use crate::easyeda::{ComponentData, EePath, EeSymbol, import_symbol};
 use crate::error::Result;
 use crate::kicad;
 use crate::library::{LibraryManager, SymbolWriteStatus};
+use crate::passives::StockPassive;
 
 pub fn convert_symbol(
     args: &Cli,
@@ -11,7 +13,39 @@ pub fn convert_symbol(
     lib_manager: &LibraryManager,
     lcsc_id: &str,
 ) -> Result<()> {
-    let ee_symbol = SymbolImporter::parse(&component_data.data_str)?;
+    // Multi-unit parts carry one document per unit in `sub_parts`
+    let parts: Vec<(&[String], f64, f64)> = if component_data.sub_parts.is_empty() {
+        vec![(
+            component_data.data_str.as_slice(),
+            component_data.bbox_x,
+            component_data.bbox_y,
+        )]
+    } else {
+        component_data
+            .sub_parts
+            .iter()
+            .map(|part| (part.data_str.as_slice(), part.bbox_x, part.bbox_y))
+            .collect()
+    };
+    let ee_symbols = parts
+        .iter()
+        .map(|(data_str, _, _)| import_symbol(data_str))
+        .collect::<Result<Vec<_>>>()?;
+    let converter = Converter::new(args.kicad_version());
+    let mut units: Vec<kicad::KiSymbolUnit> = ee_symbols
+        .iter()
+        .zip(&parts)
+        .map(|(ee_symbol, (_, bbox_x, bbox_y))| {
+            convert_unit(
+                ee_symbol,
+                *bbox_x,
+                *bbox_y,
+                args.ellipse_segments,
+                args.curve_options(),
+                &converter,
+            )
+        })
+        .collect();
 
     // Use LCSC ID as unique identifier to prevent name collisions
     let component_name = format!("{}_{}", sanitize_name(&component_data.title), lcsc_id);
@@ -19,34 +53,185 @@ pub fn convert_symbol(
 
     let mut ki_symbol = kicad::KiSymbol {
         name: component_name.clone(),
-        reference: ee_symbol.prefix.clone(),
+        reference: designator_prefix(component_data, &ee_symbols[0].prefix),
         value: component_data.title.clone(),
         description: component_data.description.clone(),
+        keywords: keywords(component_data),
         footprint: format!("{}:{}", lib_manager.footprint_lib_name(), footprint_name),
         datasheet: component_data.datasheet.clone(),
         manufacturer: component_data.manufacturer.clone(),
         lcsc_id: component_data.lcsc_id.clone(),
         jlc_id: component_data.jlc_id.clone(),
+        properties: Vec::new(),
+        extends: None,
         pins: Vec::new(),
         rectangles: Vec::new(),
         circles: Vec::new(),
         arcs: Vec::new(),
         polylines: Vec::new(),
+        beziers: Vec::new(),
         texts: Vec::new(),
+        units: Vec::new(),
     };
 
-    // Convert pins with bbox adjustment
-    let _converter = Converter::new(args.kicad_version());
+    if units.len() == 1 {
+        let unit = units.remove(0);
+        ki_symbol.pins = unit.pins;
+        ki_symbol.rectangles = unit.rectangles;
+        ki_symbol.circles = unit.circles;
+        ki_symbol.arcs = unit.arcs;
+        ki_symbol.polylines = unit.polylines;
+        ki_symbol.beziers = unit.beziers;
+        ki_symbol.texts = unit.texts;
+    } else {
+        log::info!("Converting {} symbol units", units.len());
+        ki_symbol.pins = extract_shared_pins(&mut units);
+        ki_symbol.units = units;
+    }
+
+    apply_properties(args, component_data, &mut ki_symbol)?;
+    write_symbol(args, lib_manager, &ki_symbol)
+}
+
+/// Write a generic chip resistor/capacitor as a symbol that extends a copy of
+/// `Device:R`/`Device:C` and uses KiCad's stock footprint
+pub fn convert_stock_passive(
+    args: &Cli,
+    component_data: &ComponentData,
+    lib_manager: &LibraryManager,
+    lcsc_id: &str,
+    passive: &StockPassive,
+) -> Result<()> {
+    let exporter = kicad::SymbolExporter::new(args.kicad_version());
+    let lib_path = lib_manager.get_symbol_lib_path();
+
+    // KiCad 5 has no derived symbols, so the part gets a full copy instead
+    let legacy = args.kicad_version() == KicadVersion::V5;
+
+    // The base symbol must precede its derived symbols; an existing one is kept
+    let base = passive.base_symbol();
+    if !legacy {
+        let status = lib_manager.add_or_update_component(
+            &lib_path,
+            &base.name,
+            &exporter.export(&base)?,
+            false,
+        )?;
+        if status == SymbolWriteStatus::Added {
+            log::info!("Added base symbol {}", base.name);
+        }
+    }
+
+    let mut ki_symbol = base;
+    ki_symbol.name = format!("{}_{}", sanitize_name(&component_data.title), lcsc_id);
+    ki_symbol.value = passive
+        .value
+        .clone()
+        .unwrap_or_else(|| component_data.title.clone());
+    ki_symbol.description = component_data.description.clone();
+    ki_symbol.keywords = keywords(component_data);
+    ki_symbol.footprint = passive.footprint();
+    ki_symbol.datasheet = component_data.datasheet.clone();
+    ki_symbol.manufacturer = component_data.manufacturer.clone();
+    ki_symbol.lcsc_id = component_data.lcsc_id.clone();
+    ki_symbol.jlc_id = component_data.jlc_id.clone();
+    if !legacy {
+        ki_symbol.extends = Some(passive.symbol_name().to_string());
+        ki_symbol.pins.clear();
+        ki_symbol.rectangles.clear();
+        ki_symbol.polylines.clear();
+    }
+
+    apply_properties(args, component_data, &mut ki_symbol)?;
+    write_symbol(args, lib_manager, &ki_symbol)
+}
+
+/// Search keywords from the LCSC category path, e.g. "Resistors / Chip Resistor"
+fn keywords(component_data: &ComponentData) -> String {
+    component_data
+        .category
+        .split('/')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Selected c_para parameters become hidden properties; a mapped Value
+/// replaces the title as the visible value
+fn apply_properties(
+    args: &Cli,
+    component_data: &ComponentData,
+    ki_symbol: &mut kicad::KiSymbol,
+) -> Result<()> {
+    for (name, value) in args.property_map()?.apply(&component_data.c_para) {
+        if name == "Value" {
+            ki_symbol.value = value;
+        } else {
+            ki_symbol.properties.push((name, value));
+        }
+    }
+    Ok(())
+}
+
+fn write_symbol(
+    args: &Cli,
+    lib_manager: &LibraryManager,
+    ki_symbol: &kicad::KiSymbol,
+) -> Result<()> {
+    // Export symbol
+    let exporter = kicad::SymbolExporter::new(args.kicad_version());
+    let symbol_data = exporter.export(ki_symbol)?;
+
+    let lib_path = lib_manager.get_symbol_lib_path();
+
+    // Use thread-safe add_or_update method
+    let status = lib_manager.add_or_update_component(
+        &lib_path,
+        &ki_symbol.name,
+        &symbol_data,
+        args.overwrite,
+    )?;
+
+    // KiCad 5 keeps description, keywords and datasheet in a .dcm file
+    if args.kicad_version() == KicadVersion::V5 && status != SymbolWriteStatus::Skipped {
+        lib_manager.add_or_update_doc(
+            &lib_path,
+            &ki_symbol.name,
+            &exporter.export_doc(ki_symbol),
+        )?;
+    }
+
+    match status {
+        SymbolWriteStatus::Added | SymbolWriteStatus::Updated => {
+            println!("\u{2713} Symbol converted: {}", ki_symbol.name);
+        }
+        SymbolWriteStatus::Skipped => {
+            println!("Skipped existing symbol: {}", ki_symbol.name);
+        }
+    }
+
+    Ok(())
+}
+
+/// Convert one EasyEDA symbol document, relative to its own origin
+fn convert_unit(
+    ee_symbol: &EeSymbol,
+    bbox_x: f64,
+    bbox_y: f64,
+    ellipse_segments: usize,
+    curves: CurveOptions,
+    converter: &Converter,
+) -> kicad::KiSymbolUnit {
+    let mut unit = kicad::KiSymbolUnit::default();
 
-    log::debug!(
-        "bbox_x = {}, bbox_y = {}",
-        component_data.bbox_x,
-        component_data.bbox_y
-    );
+    log::debug!("bbox_x = {}, bbox_y = {}", bbox_x, bbox_y);
+
+    // Convert pins with bbox adjustment
 
     for ee_pin in &ee_symbol.pins {
-        let adjusted_x = ee_pin.x - component_data.bbox_x;
-        let adjusted_y = ee_pin.y - component_data.bbox_y;
+        let adjusted_x = ee_pin.x - bbox_x;
+        let adjusted_y = ee_pin.y - bbox_y;
 
         if ee_pin.name.contains("PG10") {
             log::info!(
@@ -69,32 +254,33 @@ pub fn convert_symbol(
             );
         }
 
-        ki_symbol.pins.push(kicad::KiPin {
+        unit.pins.push(kicad::KiPin {
             number: ee_pin.number.clone(),
             name: ee_pin.name.clone(),
             pin_type: kicad::PinType::from_easyeda(&ee_pin.electric_type),
-            style: if ee_pin.dot {
-                kicad::PinStyle::Inverted
-            } else if ee_pin.clock {
-                kicad::PinStyle::Clock
-            } else {
-                kicad::PinStyle::Line
+            style: match (ee_pin.dot, ee_pin.clock) {
+                (true, true) => kicad::PinStyle::InvertedClock,
+                (true, false) => kicad::PinStyle::Inverted,
+                (false, true) => kicad::PinStyle::Clock,
+                (false, false) => kicad::PinStyle::Line,
             },
             pos_x: adjusted_x,
             pos_y: -adjusted_y, // Back to negation to test
             rotation: ee_pin.rotation,
             length: ee_pin.length,
+            name_visible: ee_pin.name_visible,
+            number_visible: ee_pin.number_visible,
         });
     }
 
     // Convert rectangles with bbox adjustment
-    for (_idx, ee_rect) in ee_symbol.rectangles.iter().enumerate() {
-        let adjusted_x = ee_rect.x - component_data.bbox_x;
-        let adjusted_y = component_data.bbox_y - ee_rect.y; // bbox_y - pos_y
-        let adjusted_x2 = (ee_rect.x + ee_rect.width) - component_data.bbox_x;
-        let adjusted_y2 = component_data.bbox_y - (ee_rect.y + ee_rect.height); // bbox_y - (pos_y + height)
+    for ee_rect in ee_symbol.rectangles.iter() {
+        let adjusted_x = ee_rect.x - bbox_x;
+        let adjusted_y = bbox_y - ee_rect.y; // bbox_y - pos_y
+        let adjusted_x2 = (ee_rect.x + ee_rect.width) - bbox_x;
+        let adjusted_y2 = bbox_y - (ee_rect.y + ee_rect.height); // bbox_y - (pos_y + height)
 
-        ki_symbol.rectangles.push(kicad::KiRectangle {
+        unit.rectangles.push(kicad::KiRectangle {
             x1: adjusted_x,
             y1: adjusted_y, // No negation
             x2: adjusted_x2,
@@ -106,10 +292,10 @@ pub fn convert_symbol(
 
     // Convert circles with bbox adjustment
     for ee_circle in &ee_symbol.circles {
-        let adjusted_cx = ee_circle.cx - component_data.bbox_x;
-        let adjusted_cy = component_data.bbox_y - ee_circle.cy; // bbox_y - pos_y
+        let adjusted_cx = ee_circle.cx - bbox_x;
+        let adjusted_cy = bbox_y - ee_circle.cy; // bbox_y - pos_y
 
-        ki_symbol.circles.push(kicad::KiCircle {
+        unit.circles.push(kicad::KiCircle {
             cx: adjusted_cx,
             cy: adjusted_cy, // No negation
             radius: ee_circle.radius,
@@ -119,21 +305,32 @@ pub fn convert_symbol(
     }
 
     // Convert ellipses with bbox adjustment
-    // If rx == ry, treat as circle; otherwise, approximate as circle with average radius
+    // Circles stay circles; true ellipses become closed polylines
     for ee_ellipse in &ee_symbol.ellipses {
-        let adjusted_cx = ee_ellipse.cx - component_data.bbox_x;
-        let adjusted_cy = component_data.bbox_y - ee_ellipse.cy; // bbox_y - pos_y
-
-        // Use average of rx and ry as radius (or just rx if they're equal)
-        let radius = (ee_ellipse.rx + ee_ellipse.ry) / 2.0;
-
-        ki_symbol.circles.push(kicad::KiCircle {
-            cx: adjusted_cx,
-            cy: adjusted_cy, // No negation
-            radius,
-            stroke_width: ee_ellipse.stroke_width,
-            fill: ee_ellipse.fill,
-        });
+        let adjusted_cx = ee_ellipse.cx - bbox_x;
+        let adjusted_cy = bbox_y - ee_ellipse.cy; // bbox_y - pos_y
+
+        if (ee_ellipse.rx - ee_ellipse.ry).abs() < 1e-6 {
+            unit.circles.push(kicad::KiCircle {
+                cx: adjusted_cx,
+                cy: adjusted_cy, // No negation
+                radius: ee_ellipse.rx,
+                stroke_width: ee_ellipse.stroke_width,
+                fill: ee_ellipse.fill,
+            });
+        } else {
+            unit.polylines.push(kicad::KiPolyline {
+                points: ellipse_points(
+                    adjusted_cx,
+                    adjusted_cy,
+                    ee_ellipse.rx,
+                    ee_ellipse.ry,
+                    ellipse_segments,
+                ),
+                stroke_width: ee_ellipse.stroke_width,
+                fill: ee_ellipse.fill,
+            });
+        }
     }
 
     // Convert arcs with bbox adjustment
@@ -158,14 +355,14 @@ pub fn convert_symbol(
         let mid_y = ee_arc.y + ee_arc.radius * mid_angle_rad.sin();
 
         // Apply bbox adjustment
-        let adjusted_start_x = start_x - component_data.bbox_x;
-        let adjusted_start_y = component_data.bbox_y - start_y;
-        let adjusted_mid_x = mid_x - component_data.bbox_x;
-        let adjusted_mid_y = component_data.bbox_y - mid_y;
-        let adjusted_end_x = end_x - component_data.bbox_x;
-        let adjusted_end_y = component_data.bbox_y - end_y;
-
-        ki_symbol.arcs.push(kicad::SymbolKiArc {
+        let adjusted_start_x = start_x - bbox_x;
+        let adjusted_start_y = bbox_y - start_y;
+        let adjusted_mid_x = mid_x - bbox_x;
+        let adjusted_mid_y = bbox_y - mid_y;
+        let adjusted_end_x = end_x - bbox_x;
+        let adjusted_end_y = bbox_y - end_y;
+
+        unit.arcs.push(kicad::SymbolKiArc {
             start_x: adjusted_start_x,
             start_y: adjusted_start_y,
             mid_x: adjusted_mid_x,
@@ -182,13 +379,13 @@ pub fn convert_symbol(
             .points
             .iter()
             .map(|(x, y)| {
-                let adj_x = x - component_data.bbox_x;
-                let adj_y = component_data.bbox_y - y; // bbox_y - pos_y
+                let adj_x = x - bbox_x;
+                let adj_y = bbox_y - y; // bbox_y - pos_y
                 (adj_x, adj_y) // No negation
             })
             .collect();
 
-        ki_symbol.polylines.push(kicad::KiPolyline {
+        unit.polylines.push(kicad::KiPolyline {
             points: adjusted_points,
             stroke_width: ee_polyline.stroke_width,
             fill: false,
@@ -201,82 +398,30 @@ pub fn convert_symbol(
             .points
             .iter()
             .map(|(x, y)| {
-                let adj_x = x - component_data.bbox_x;
-                let adj_y = component_data.bbox_y - y; // bbox_y - pos_y
+                let adj_x = x - bbox_x;
+                let adj_y = bbox_y - y; // bbox_y - pos_y
                 (adj_x, adj_y) // No negation
             })
             .collect();
 
-        ki_symbol.polylines.push(kicad::KiPolyline {
+        unit.polylines.push(kicad::KiPolyline {
             points: adjusted_points,
             stroke_width: ee_polygon.stroke_width,
             fill: ee_polygon.fill,
         });
     }
 
-    // Convert paths to polylines with bbox adjustment
-    // Parse SVG path commands (M, L, Z) and convert to polylines
+    // Convert paths to polylines with bbox adjustment, one polyline per subpath
     for ee_path in &ee_symbol.paths {
-        let path_str = &ee_path.path_data;
-        let tokens: Vec<&str> = path_str.split_whitespace().collect();
-        let mut points = Vec::new();
-        let mut i = 0;
-
-        while i < tokens.len() {
-            let token = tokens[i];
-            match token {
-                "M" | "L" => {
-                    // Move or Line command, followed by x,y coordinates
-                    if i + 1 < tokens.len() {
-                        i += 1;
-                        // Parse coordinate pair (may be "x,y" or separate "x" "y")
-                        let coord_str = tokens[i];
-                        if let Some((x_str, y_str)) = coord_str.split_once(',') {
-                            if let (Ok(x), Ok(y)) = (x_str.parse::<f64>(), y_str.parse::<f64>()) {
-                                let adj_x = x - component_data.bbox_x;
-                                let adj_y = component_data.bbox_y - y;
-                                points.push((adj_x, adj_y));
-                            }
-                        } else if i + 1 < tokens.len() {
-                            // Separate x and y
-                            if let (Ok(x), Ok(y)) =
-                                (tokens[i].parse::<f64>(), tokens[i + 1].parse::<f64>())
-                            {
-                                let adj_x = x - component_data.bbox_x;
-                                let adj_y = component_data.bbox_y - y;
-                                points.push((adj_x, adj_y));
-                                i += 1;
-                            }
-                        }
-                    }
-                }
-                "Z" | "z" => {
-                    // Close path: add line from current point back to start point
-                    if !points.is_empty() {
-                        let first_point = points[0];
-                        points.push(first_point);
-                    }
-                }
-                _ => {}
-            }
-            i += 1;
-        }
-
-        if points.len() >= 2 {
-            ki_symbol.polylines.push(kicad::KiPolyline {
-                points,
-                stroke_width: ee_path.stroke_width,
-                fill: ee_path.fill,
-            });
-        }
+        convert_path(ee_path, bbox_x, bbox_y, curves, converter, &mut unit);
     }
 
     // Convert texts with bbox adjustment
     for ee_text in &ee_symbol.texts {
-        let adjusted_x = ee_text.x - component_data.bbox_x;
-        let adjusted_y = component_data.bbox_y - ee_text.y;
+        let adjusted_x = ee_text.x - bbox_x;
+        let adjusted_y = bbox_y - ee_text.y;
 
-        ki_symbol.texts.push(kicad::SymbolKiText {
+        unit.texts.push(kicad::SymbolKiText {
             text: ee_text.text.clone(),
             x: adjusted_x,
             y: adjusted_y,
@@ -285,28 +430,480 @@ pub fn convert_symbol(
         });
     }
 
-    // Export symbol
-    let exporter = kicad::SymbolExporter::new(args.kicad_version());
-    let symbol_data = exporter.export(&ki_symbol)?;
+    unit
+}
 
-    let lib_path = lib_manager.get_symbol_lib_path();
+/// Convert an SVG path into polylines, one per subpath.
+///
+/// Curves and arcs are flattened to `curves.tolerance`; with `curves.native`,
+/// cubic and quadratic segments of unfilled paths become `bezier` items instead.
+fn convert_path(
+    ee_path: &EePath,
+    bbox_x: f64,
+    bbox_y: f64,
+    curves: CurveOptions,
+    converter: &Converter,
+    unit: &mut kicad::KiSymbolUnit,
+) {
+    let commands = match parse_svg_path(&ee_path.path_data) {
+        Ok(commands) => commands,
+        Err(e) => {
+            log::warn!("Skipping symbol path {}: {}", ee_path.path_data, e);
+            return;
+        }
+    };
 
-    // Use thread-safe add_or_update method
-    let status = lib_manager.add_or_update_component(
-        &lib_path,
-        &ki_symbol.name,
-        &symbol_data,
-        args.overwrite,
-    )?;
+    let adjust = |(x, y): (f64, f64)| (x - bbox_x, bbox_y - y);
+    let native = curves.native && !ee_path.fill;
+
+    let mut subpaths: Vec<Vec<(f64, f64)>> = Vec::new();
+    // Current point and subpath start, in EasyEDA coordinates
+    let mut current = (0.0, 0.0);
+    let mut start = (0.0, 0.0);
+
+    for cmd in commands {
+        // Cubic control points of curve segments
+        let curve = match cmd {
+            SvgCommand::MoveTo { x, y } => {
+                subpaths.push(vec![adjust((x, y))]);
+                current = (x, y);
+                start = current;
+                continue;
+            }
+            SvgCommand::LineTo { x, y } => {
+                current_subpath(&mut subpaths, adjust(current)).push(adjust((x, y)));
+                current = (x, y);
+                continue;
+            }
+            SvgCommand::Arc {
+                rx,
+                ry,
+                angle,
+                large_arc,
+                sweep,
+                x,
+                y,
+            } => {
+                let points = current_subpath(&mut subpaths, adjust(current));
+                match converter.svg_arc(current, (x, y), (rx, ry), angle, large_arc, sweep) {
+                    Ok(arc) => points.extend(arc.flatten(curves.tolerance).into_iter().map(adjust)),
+                    Err(_) => points.push(adjust((x, y))),
+                }
+                current = (x, y);
+                continue;
+            }
+            // Close path: add line from current point back to start point
+            SvgCommand::ClosePath => {
+                if let Some(points) = subpaths.last_mut() {
+                    points.push(adjust(start));
+                }
+                current = start;
+                continue;
+            }
+            SvgCommand::CubicTo {
+                x1,
+                y1,
+                x2,
+                y2,
+                x,
+                y,
+            } => ((x1, y1), (x2, y2), (x, y)),
+            SvgCommand::QuadTo { x1, y1, x, y } => {
+                let (c1, c2) = quad_to_cubic(current, (x1, y1), (x, y));
+                (c1, c2, (x, y))
+            }
+        };
 
-    match status {
-        SymbolWriteStatus::Added | SymbolWriteStatus::Updated => {
-            println!("\u{2713} Symbol converted: {}", ki_symbol.name);
+        let (c1, c2, end) = curve;
+        if native {
+            unit.beziers.push(kicad::KiBezier {
+                points: [adjust(current), adjust(c1), adjust(c2), adjust(end)],
+                stroke_width: ee_path.stroke_width,
+                fill: false,
+            });
+            // Following lines start a new polyline at the curve's end
+            subpaths.push(vec![adjust(end)]);
+        } else {
+            let points = current_subpath(&mut subpaths, adjust(current));
+            for point in flatten_cubic(current, c1, c2, end, curves.tolerance) {
+                points.push(adjust(point));
+            }
         }
-        SymbolWriteStatus::Skipped => {
-            println!("Skipped existing symbol: {}", ki_symbol.name);
+        current = end;
+    }
+
+    for points in subpaths.into_iter().filter(|p| p.len() >= 2) {
+        unit.polylines.push(kicad::KiPolyline {
+            points,
+            stroke_width: ee_path.stroke_width,
+            fill: ee_path.fill,
+        });
+    }
+}
+
+/// Polyline being drawn, started at `from` if the path has no move-to yet
+fn current_subpath(subpaths: &mut Vec<Vec<(f64, f64)>>, from: (f64, f64)) -> &mut Vec<(f64, f64)> {
+    if subpaths.is_empty() {
+        subpaths.push(vec![from]);
+    }
+    subpaths.last_mut().expect("subpaths is not empty")
+}
+
+/// Closed polygon approximating an axis-aligned ellipse; the first point is repeated at the end
+fn ellipse_points(cx: f64, cy: f64, rx: f64, ry: f64, segments: usize) -> Vec<(f64, f64)> {
+    let segments = segments.max(8);
+    (0..=segments)
+        .map(|i| {
+            let angle = std::f64::consts::TAU * (i % segments) as f64 / segments as f64;
+            (cx + rx * angle.cos(), cy + ry * angle.sin())
+        })
+        .collect()
+}
+
+/// Category keywords and the designator prefix they imply, checked in order.
+/// IC families come first so "LED Drivers" or "MOSFET Gate Drivers" stay `U`,
+/// and specific parts precede the broader word they are often listed with, as
+/// in "Potentiometers, Variable Resistors".
+const CATEGORY_PREFIXES: &[(&str, &str)] = &[
+    ("ic", "U"),
+    ("driver", "U"),
+    ("controller", "U"),
+    ("regulator", "U"),
+    ("amplifier", "U"),
+    ("potentiometer", "RV"),
+    ("resistor", "R"),
+    ("capacitor", "C"),
+    ("inductor", "L"),
+    ("ferrite", "FB"),
+    ("crystal", "Y"),
+    ("oscillator", "Y"),
+    ("resonator", "Y"),
+    ("led", "D"),
+    ("diode", "D"),
+    ("rectifier", "D"),
+    ("tvs", "D"),
+    ("transistor", "Q"),
+    ("mosfet", "Q"),
+    ("igbt", "Q"),
+    ("switch", "SW"),
+    ("button", "SW"),
+    ("connector", "J"),
+    ("header", "J"),
+    ("socket", "J"),
+    ("fuse", "F"),
+    ("relay", "K"),
+    ("transformer", "T"),
+    ("buzzer", "BZ"),
+    ("speaker", "LS"),
+];
+
+/// Reference designator prefix: `c_para.pre`, then the symbol document,
+/// then the category rule table, then `U`
+pub fn designator_prefix(component_data: &ComponentData, symbol_prefix: &str) -> String {
+    let pre = component_data
+        .c_para
+        .get("pre")
+        .map(|p| p.trim().trim_end_matches('?').trim())
+        .unwrap_or("");
+    if !pre.is_empty() {
+        return pre.to_string();
+    }
+    if !symbol_prefix.is_empty() {
+        return symbol_prefix.to_string();
+    }
+
+    // The category is the most reliable hint; LCSC descriptions usually end in it
+    for text in [&component_data.category, &component_data.description] {
+        let words: Vec<String> = text
+            .to_lowercase()
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .map(|w| w.to_string())
+            .collect();
+        let found = CATEGORY_PREFIXES.iter().find(|(keyword, _)| {
+            words.iter().any(|w| {
+                w == keyword
+                    || w.strip_suffix('s') == Some(keyword)
+                    || w.strip_suffix("es") == Some(keyword)
+            })
+        });
+        if let Some((_, prefix)) = found {
+            return prefix.to_string();
         }
     }
 
-    Ok(())
+    "U".to_string()
+}
+
+/// Pins with the same number in several units (typically the supply pins
+/// repeated on every gate) become common pins shared by all units.
+/// The first unit's placement is kept.
+fn extract_shared_pins(units: &mut [kicad::KiSymbolUnit]) -> Vec<kicad::KiPin> {
+    let mut shared: Vec<kicad::KiPin> = Vec::new();
+
+    for i in 0..units.len() {
+        for pin in &units[i].pins {
+            let repeated = units
+                .iter()
+                .enumerate()
+                .any(|(j, other)| j != i && other.pins.iter().any(|p| p.number == pin.number));
+            if repeated && !shared.iter().any(|p| p.number == pin.number) {
+                shared.push(pin.clone());
+            }
+        }
+    }
+
+    for unit in units.iter_mut() {
+        unit.pins
+            .retain(|pin| !shared.iter().any(|p| p.number == pin.number));
+    }
+
+    shared
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kicad::{KiPin, KiSymbol, KiSymbolUnit, PinStyle, PinType, SymbolExporter};
+
+    fn pin(number: &str, pin_type: PinType) -> KiPin {
+        KiPin {
+            number: number.to_string(),
+            name: number.to_string(),
+            pin_type,
+            style: PinStyle::Line,
+            pos_x: 0.0,
+            pos_y: number.parse::<f64>().unwrap(),
+            rotation: 0,
+            length: 2.54,
+            name_visible: true,
+            number_visible: true,
+        }
+    }
+
+    fn component(c_para: &[(&str, &str)], category: &str, description: &str) -> ComponentData {
+        let mut data: ComponentData = serde_json::from_value(serde_json::json!({
+            "lcsc_id": "C1", "title": "Part", "description": description,
+            "data_str": [], "bbox_x": 0.0, "bbox_y": 0.0,
+            "package_detail": [], "package_bbox_x": 0.0, "package_bbox_y": 0.0,
+            "model_3d": null, "manufacturer": "", "datasheet": "", "jlc_id": "",
+            "category": category
+        }))
+        .unwrap();
+        data.c_para = c_para
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        data
+    }
+
+    #[test]
+    fn ellipse_becomes_closed_polyline() {
+        let points = ellipse_points(10.0, 5.0, 4.0, 2.0, 16);
+        assert_eq!(points.len(), 17);
+        assert_eq!(points.first(), points.last());
+        assert!((points[0].0 - 14.0).abs() < 1e-9);
+        assert!((points[4].1 - 7.0).abs() < 1e-9);
+        assert_eq!(ellipse_points(0.0, 0.0, 1.0, 2.0, 3).len(), 9);
+    }
+
+    #[test]
+    fn path_curves_are_flattened_or_kept_native() {
+        let path = EePath {
+            path_data: "M0 0L10 0C10 10 20 10 20 0".to_string(),
+            stroke_width: 1.0,
+            fill: false,
+        };
+        let mut curves = CurveOptions {
+            tolerance: 0.05,
+            native: false,
+        };
+        let converter = Converter::new(KicadVersion::V6);
+
+        let mut unit = KiSymbolUnit::default();
+        convert_path(&path, 0.0, 0.0, curves, &converter, &mut unit);
+        assert_eq!(unit.polylines.len(), 1);
+        let points = &unit.polylines[0].points;
+        assert!(points.len() > 4);
+        assert_eq!(points.last(), Some(&(20.0, 0.0)));
+        // Y is flipped, so the curve bulges downwards
+        assert!(points.iter().any(|p| p.1 < -7.0));
+
+        curves.native = true;
+        let mut unit = KiSymbolUnit::default();
+        convert_path(&path, 0.0, 0.0, curves, &converter, &mut unit);
+        assert_eq!(unit.polylines[0].points, vec![(0.0, 0.0), (10.0, 0.0)]);
+        assert_eq!(
+            unit.beziers[0].points,
+            [(10.0, 0.0), (10.0, -10.0), (20.0, -10.0), (20.0, 0.0)]
+        );
+    }
+
+    #[test]
+    fn path_arcs_are_flattened() {
+        let path = EePath {
+            path_data: "M0 0A10 10 0 0 1 20 0".to_string(),
+            stroke_width: 1.0,
+            fill: false,
+        };
+        let curves = CurveOptions {
+            tolerance: 0.05,
+            native: true,
+        };
+        let converter = Converter::new(KicadVersion::V6);
+
+        let mut unit = KiSymbolUnit::default();
+        convert_path(&path, 0.0, 0.0, curves, &converter, &mut unit);
+        assert!(unit.beziers.is_empty());
+        let points = &unit.polylines[0].points;
+        assert!(points.len() > 4);
+        let end = points.last().unwrap();
+        assert!((end.0 - 20.0).abs() < 1e-9 && end.1.abs() < 1e-9);
+        // Every point lies on the circle, bulging upwards once Y is flipped
+        for p in points {
+            assert!(((p.0 - 10.0).hypot(p.1) - 10.0).abs() < 1e-9);
+        }
+        assert!(points.iter().any(|p| p.1 > 9.9));
+    }
+
+    #[test]
+    fn kicad5_export_has_arcs_texts_and_doc() {
+        let mut symbol = StockPassive {
+            kind: crate::passives::PassiveKind::Resistor,
+            size: "0603",
+            metric: "1608",
+            value: None,
+        }
+        .base_symbol();
+        symbol.datasheet = "https://example.com/r.pdf".to_string();
+        symbol.value = r#"10k "1%" C:\R"#.to_string();
+        // Quarter circle from (10, 0) counter-clockwise to (0, 10)
+        let h = 10.0 * std::f64::consts::FRAC_1_SQRT_2;
+        symbol.arcs.push(kicad::SymbolKiArc {
+            start_x: 10.0,
+            start_y: 0.0,
+            mid_x: h,
+            mid_y: h,
+            end_x: 0.0,
+            end_y: 10.0,
+            stroke_width: 1.0,
+        });
+        symbol.texts.push(kicad::SymbolKiText {
+            text: "R 1".to_string(),
+            x: 0.0,
+            y: 0.0,
+            rotation: 90.0,
+            font_size: 7.0,
+        });
+
+        let exporter = SymbolExporter::new(KicadVersion::V5);
+        let output = exporter.export(&symbol).unwrap();
+        // Pin 1 sits above the body and points down, as in Device:R
+        assert!(output.contains("X ~ 1 0 150 50 D 50 50 1 1 P\n"));
+        assert!(output.contains("A 0 0 100 0 900 1 1 10 N 100 0 0 100\n"));
+        assert!(output.contains("T 900 0 0 41 0 1 1 \"R 1\" Normal 0 C C\n"));
+        assert!(output.contains(r#"F1 "10k \"1%\" C:\\R" 0 -100 50 H V C CNN"#));
+
+        assert_eq!(
+            exporter.export_doc(&symbol),
+            "$CMP R\nD Resistor\nK R res resistor\nF https://example.com/r.pdf\n$ENDCMP\n"
+        );
+    }
+
+    #[test]
+    fn designator_prefix_prefers_c_para_then_category() {
+        assert_eq!(
+            designator_prefix(&component(&[("pre", "SW?")], "", ""), "U"),
+            "SW"
+        );
+        assert_eq!(
+            designator_prefix(&component(&[], "Resistors / Chip Resistor", ""), ""),
+            "R"
+        );
+        assert_eq!(
+            designator_prefix(
+                &component(&[], "", "16MHz ±10ppm 20pF SMD3225-4P Crystals"),
+                ""
+            ),
+            "Y"
+        );
+        assert_eq!(
+            designator_prefix(
+                &component(&[], "Power Management ICs / LED Drivers", ""),
+                ""
+            ),
+            "U"
+        );
+        assert_eq!(
+            designator_prefix(&component(&[], "Switches / Tactile Switches", ""), ""),
+            "SW"
+        );
+        assert_eq!(
+            designator_prefix(
+                &component(&[], "Potentiometers, Variable Resistors", ""),
+                ""
+            ),
+            "RV"
+        );
+        assert_eq!(designator_prefix(&component(&[], "", ""), ""), "U");
+    }
+
+    #[test]
+    fn shared_power_pins_move_to_common_unit() {
+        let mut units = vec![
+            KiSymbolUnit {
+                pins: vec![pin("1", PinType::Output), pin("4", PinType::PowerIn)],
+                ..Default::default()
+            },
+            KiSymbolUnit {
+                pins: vec![pin("7", PinType::Output), pin("4", PinType::PowerIn)],
+                ..Default::default()
+            },
+        ];
+
+        let shared = extract_shared_pins(&mut units);
+        assert_eq!(shared.len(), 1);
+        assert_eq!(shared[0].number, "4");
+
+        let symbol = KiSymbol {
+            name: "LM358_C7950".to_string(),
+            reference: "U".to_string(),
+            value: "LM358".to_string(),
+            description: String::new(),
+            keywords: String::new(),
+            footprint: String::new(),
+            datasheet: String::new(),
+            manufacturer: String::new(),
+            lcsc_id: "C7950".to_string(),
+            jlc_id: String::new(),
+            properties: Vec::new(),
+            extends: None,
+            pins: shared,
+            rectangles: Vec::new(),
+            circles: Vec::new(),
+            arcs: Vec::new(),
+            polylines: Vec::new(),
+            beziers: Vec::new(),
+            texts: Vec::new(),
+            units,
+        };
+        let output = SymbolExporter::new(KicadVersion::V6)
+            .export(&symbol)
+            .unwrap();
+
+        assert!(output.contains("(symbol \"LM358_C7950_0_1\""));
+        assert!(output.contains("(symbol \"LM358_C7950_1_1\""));
+        assert!(output.contains("(symbol \"LM358_C7950_2_1\""));
+        assert_eq!(output.matches("(number \"4\"").count(), 1);
+        assert!(output.contains("(id 0)") && !output.contains("exclude_from_sim"));
+
+        // KiCad 9: no property ids, `(hide yes)` and sim/embedded-font flags
+        let output = SymbolExporter::new(KicadVersion::V9)
+            .export(&symbol)
+            .unwrap();
+        assert!(!output.contains("(id "));
+        assert!(output.contains("(exclude_from_sim no)"));
+        assert!(output.contains("(effects (font (size 1.27 1.27)) (hide yes))"));
+        assert!(output.ends_with("    (embedded_fonts no)\n  )\n"));
+    }
 }